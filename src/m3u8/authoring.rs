@@ -0,0 +1,407 @@
+//! Conformance checks for the Apple HLS Authoring Specification.
+//!
+//! The authoring specification adds delivery requirements on top of RFC 8216, e.g. that
+//! every variant declares `CODECS` and `AVERAGE-BANDWIDTH`. [`Checker`] runs all of its
+//! rules over a [`Playlist`] and returns a [`Report`] with one [`RuleResult`] per rule,
+//! passed or not, similar to the output of Apple's `mediastreamvalidator`.
+//!
+//! Rules that do not apply to a playlist, e.g. variant rules on a Media Playlist, pass.
+//! Rules that lack the data to decide, e.g. `apple-segment-bitrate` without a declared
+//! bandwidth, are skipped; a skipped rule does not fail the report.
+//!
+//! # Example
+//!
+//! ```
+//! use m3u8_parser::m3u8::authoring::Checker;
+//! use m3u8_parser::m3u8::playlist::Playlist;
+//!
+//! let data = "#EXTM3U
+//! #EXT-X-STREAM-INF:BANDWIDTH=1280000,RESOLUTION=640x360
+//! low.m3u8
+//! ";
+//! let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+//! let report = Checker::new().check(&playlist);
+//!
+//! assert!(!report.passed());
+//! assert!(!report.result("apple-codecs").unwrap().passed());
+//! println!("{}", report);
+//! ```
+
+use crate::m3u8::playlist::master::{MasterPlaylist, Variant};
+use crate::m3u8::playlist::media::MediaPlaylist;
+use crate::m3u8::playlist::{Playlist, PlaylistKind};
+use crate::m3u8::tags::Tag;
+use crate::m3u8::values::Decimal;
+use std::fmt;
+
+/// The longest target duration the specification recommends, in seconds.
+const MAX_TARGET_DURATION: u64 = 6;
+
+/// How far the segment bit rates may deviate from the declared `BANDWIDTH`, as a fraction.
+const BANDWIDTH_TOLERANCE: f64 = 0.1;
+
+/// Codec prefixes that identify video in a `CODECS` attribute.
+const VIDEO_CODECS: [&str; 7] = ["avc1", "avc3", "hvc1", "hev1", "dvh1", "dvhe", "av01"];
+
+/// What the rules look at.
+struct Context<'a> {
+    playlist: &'a Playlist,
+    /// The typed view of a Master Playlist, or `None` for a Media Playlist.
+    master: Option<MasterPlaylist>,
+    /// The typed view of a Media Playlist, or `None` for a Master Playlist.
+    media: Option<MediaPlaylist>,
+    /// The `BANDWIDTH` declared for the Media Playlist.
+    bandwidth: Option<u32>,
+}
+
+/// Returns the failures of a rule, or why the rule could not be evaluated.
+type Rule = fn(&Context) -> Result<Vec<Failure>, &'static str>;
+
+/// The rules checked by [`Checker`], with their descriptions.
+const RULES: [(&str, &str, Rule); 7] = [
+    (
+        "apple-independent-segments",
+        "EXT-X-INDEPENDENT-SEGMENTS is present",
+        independent_segments,
+    ),
+    (
+        "apple-average-bandwidth",
+        "Every EXT-X-STREAM-INF has AVERAGE-BANDWIDTH",
+        |context| {
+            Ok(missing_attribute(context, "AVERAGE-BANDWIDTH", |variant| {
+                variant.average_bandwidth.is_some()
+            }))
+        },
+    ),
+    (
+        "apple-codecs",
+        "Every EXT-X-STREAM-INF has CODECS",
+        |context| {
+            Ok(missing_attribute(context, "CODECS", |variant| {
+                variant.codecs.is_some()
+            }))
+        },
+    ),
+    (
+        "apple-frame-rate",
+        "Every video variant has FRAME-RATE",
+        |context| {
+            Ok(missing_attribute(context, "FRAME-RATE", |variant| {
+                !is_video(variant) || variant.frame_rate.is_some()
+            }))
+        },
+    ),
+    (
+        "apple-iframe-variants",
+        "Every video variant has an I-frame variant of the same resolution",
+        iframe_variants,
+    ),
+    (
+        "apple-target-duration",
+        "EXT-X-TARGETDURATION is at most 6 seconds",
+        target_duration,
+    ),
+    (
+        "apple-segment-bitrate",
+        "Segment bit rates are within 10% of the declared BANDWIDTH",
+        segment_bitrate,
+    ),
+];
+
+/// A reason why a rule did not pass.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Failure {
+    /// The index of the offending tag in `Playlist::tags`, if the failure concerns one tag.
+    pub index: Option<usize>,
+    /// A description of the problem.
+    pub message: String,
+}
+
+/// The outcome of a single rule.
+#[derive(Debug, PartialEq, Clone)]
+pub struct RuleResult {
+    /// The rule ID, e.g. `apple-codecs`.
+    pub id: &'static str,
+    /// What the rule requires.
+    pub description: &'static str,
+    /// Why the rule was not evaluated, e.g. `no declared BANDWIDTH`. `None` if it was.
+    pub skipped: Option<&'static str>,
+    /// Why the rule did not pass. Empty if it passed or was skipped.
+    pub failures: Vec<Failure>,
+}
+
+impl RuleResult {
+    /// Returns `true` if the rule did not fail. Skipped rules pass.
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Returns `true` if the rule was not evaluated for lack of data.
+    pub fn is_skipped(&self) -> bool {
+        self.skipped.is_some()
+    }
+}
+
+/// The outcome of every rule, in the order of [`Checker::rule_ids`].
+#[derive(Debug, PartialEq, Clone)]
+pub struct Report {
+    pub results: Vec<RuleResult>,
+}
+
+impl Report {
+    /// Returns `true` if no rule failed.
+    pub fn passed(&self) -> bool {
+        self.results.iter().all(RuleResult::passed)
+    }
+
+    /// Returns the result of the rule `id`.
+    pub fn result(&self, id: &str) -> Option<&RuleResult> {
+        self.results.iter().find(|result| result.id == id)
+    }
+
+    /// Returns the results of the rules that did not pass.
+    pub fn failed(&self) -> impl Iterator<Item = &RuleResult> {
+        self.results.iter().filter(|result| !result.passed())
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for result in &self.results {
+            let status = match (result.skipped, result.passed()) {
+                (Some(_), _) => "SKIP",
+                (None, true) => "PASS",
+                (None, false) => "FAIL",
+            };
+            writeln!(f, "{} {}: {}", status, result.id, result.description)?;
+            if let Some(reason) = result.skipped {
+                writeln!(f, "    {}", reason)?;
+            }
+            for failure in &result.failures {
+                match failure.index {
+                    Some(index) => writeln!(f, "    tag {}: {}", index, failure.message)?,
+                    None => writeln!(f, "    {}", failure.message)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Checks playlists against the Apple HLS Authoring Specification.
+///
+/// The checker only sees the playlist, not the media. `apple-segment-bitrate` estimates
+/// each segment's bit rate from the length of its `EXT-X-BYTERANGE` or from the
+/// `EXT-X-BITRATE` in effect, ignores segments with neither, and is skipped without a
+/// [`declared_bandwidth`](Checker::declared_bandwidth) or when no segment has a bit rate.
+#[derive(Debug, Clone, Default)]
+pub struct Checker {
+    bandwidth: Option<u32>,
+}
+
+impl Checker {
+    /// Creates a new `Checker`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the `BANDWIDTH` that the Master Playlist declares for the Media Playlist being
+    /// checked. Without it, `apple-segment-bitrate` is skipped.
+    pub fn declared_bandwidth(self, bandwidth: u32) -> Self {
+        Self {
+            bandwidth: Some(bandwidth),
+        }
+    }
+
+    /// Returns the IDs of all rules, in the order they appear in a [`Report`].
+    pub fn rule_ids() -> impl Iterator<Item = &'static str> {
+        RULES.iter().map(|(id, ..)| *id)
+    }
+
+    /// Runs every rule over the playlist.
+    pub fn check(&self, playlist: &Playlist) -> Report {
+        let kind = playlist.kind();
+        let context = Context {
+            playlist,
+            master: (kind != PlaylistKind::Media).then(|| MasterPlaylist::from(playlist)),
+            media: (kind != PlaylistKind::Master).then(|| MediaPlaylist::from(playlist)),
+            bandwidth: self.bandwidth,
+        };
+
+        let results = RULES
+            .iter()
+            .map(|(id, description, rule)| {
+                let (skipped, failures) = match rule(&context) {
+                    Ok(failures) => (None, failures),
+                    Err(reason) => (Some(reason), Vec::new()),
+                };
+                RuleResult {
+                    id,
+                    description,
+                    skipped,
+                    failures,
+                }
+            })
+            .collect();
+
+        Report { results }
+    }
+}
+
+/// Returns `true` if the variant carries video, judging by its `RESOLUTION` and `CODECS`.
+fn is_video(variant: &Variant) -> bool {
+    variant.resolution.is_some()
+        || variant.codecs.as_deref().is_some_and(|codecs| {
+            codecs.split(',').any(|codec| {
+                VIDEO_CODECS
+                    .iter()
+                    .any(|video| codec.trim().starts_with(video))
+            })
+        })
+}
+
+/// Returns the variants of the Master Playlist with the index of their tag.
+fn variants<'a>(context: &'a Context) -> impl Iterator<Item = (usize, &'a Variant)> {
+    let indices = context
+        .playlist
+        .tags
+        .iter()
+        .enumerate()
+        .filter(|(_, tag)| matches!(tag, Tag::ExtXStreamInf { .. }))
+        .map(|(index, _)| index);
+    indices.zip(context.master.iter().flat_map(|master| &master.variants))
+}
+
+fn independent_segments(context: &Context) -> Result<Vec<Failure>, &'static str> {
+    if context
+        .playlist
+        .tags
+        .iter()
+        .any(|tag| matches!(tag, Tag::ExtXIndependentSegments))
+    {
+        Ok(Vec::new())
+    } else {
+        Ok(vec![Failure {
+            index: None,
+            message: "EXT-X-INDEPENDENT-SEGMENTS is missing".to_string(),
+        }])
+    }
+}
+
+fn missing_attribute(
+    context: &Context,
+    attribute: &str,
+    has_attribute: impl Fn(&Variant) -> bool,
+) -> Vec<Failure> {
+    variants(context)
+        .filter(|(_, variant)| !has_attribute(variant))
+        .map(|(index, variant)| Failure {
+            index: Some(index),
+            message: format!("variant {} has no {}", variant.uri, attribute),
+        })
+        .collect()
+}
+
+fn iframe_variants(context: &Context) -> Result<Vec<Failure>, &'static str> {
+    let iframe_variants = context
+        .master
+        .as_ref()
+        .map_or(&[][..], |master| &master.iframe_variants);
+    Ok(variants(context)
+        .filter(|(_, variant)| is_video(variant))
+        .filter(|(_, variant)| {
+            !iframe_variants.iter().any(|iframe| {
+                variant.resolution.is_none() || iframe.resolution == variant.resolution
+            })
+        })
+        .map(|(index, variant)| Failure {
+            index: Some(index),
+            message: format!("variant {} has no matching I-frame variant", variant.uri),
+        })
+        .collect())
+}
+
+fn target_duration(context: &Context) -> Result<Vec<Failure>, &'static str> {
+    Ok(
+        match context
+            .media
+            .as_ref()
+            .and_then(|media| media.target_duration())
+        {
+            Some(duration) if duration > MAX_TARGET_DURATION => vec![Failure {
+                index: context
+                    .playlist
+                    .tags
+                    .iter()
+                    .position(|tag| matches!(tag, Tag::ExtXTargetDuration(_))),
+                message: format!(
+                    "target duration is {} seconds, {} is recommended",
+                    duration, MAX_TARGET_DURATION
+                ),
+            }],
+            _ => Vec::new(),
+        },
+    )
+}
+
+/// Compares the bit rate of each segment with the declared bandwidth. The bit rate is
+/// taken from the length of the segment's `EXT-X-BYTERANGE`, or else from the
+/// `EXT-X-BITRATE` in effect. No segment may exceed the bandwidth by more than the
+/// tolerance, and the peak may not fall short of it by more than the tolerance.
+fn segment_bitrate(context: &Context) -> Result<Vec<Failure>, &'static str> {
+    let Some(media) = &context.media else {
+        return Ok(Vec::new());
+    };
+    let bandwidth = context.bandwidth.ok_or("no declared BANDWIDTH")?;
+    let upper_limit = bandwidth as f64 * (1.0 + BANDWIDTH_TOLERANCE);
+    let lower_limit = bandwidth as f64 * (1.0 - BANDWIDTH_TOLERANCE);
+    let mut bitrate_tag: Option<u32> = None;
+    let mut peak: Option<f64> = None;
+    let mut failures = Vec::new();
+
+    // The index of the segment's EXTINF tag in the playlist.
+    let mut index = media.header.len();
+    for segment in &media.segments {
+        for tag in &segment.tags {
+            if let Tag::ExtXBitrate(kbps) = tag {
+                bitrate_tag = Some(*kbps);
+            }
+        }
+        index += segment.tags.len();
+
+        let length = segment.byte_range.map(|range| range.length);
+        let bitrate = match (length, bitrate_tag) {
+            (Some(length), _) if segment.duration > Decimal::ZERO => {
+                Some(length as f64 * 8.0 / segment.duration.as_f64())
+            }
+            (None, Some(kbps)) => Some(kbps as f64 * 1000.0),
+            _ => None,
+        };
+        if let Some(bitrate) = bitrate {
+            peak = Some(peak.map_or(bitrate, |peak: f64| peak.max(bitrate)));
+            if bitrate > upper_limit {
+                failures.push(Failure {
+                    index: Some(index),
+                    message: format!(
+                        "segment {} has a bit rate of {:.0} bit/s, declared BANDWIDTH is {}",
+                        segment.uri, bitrate, bandwidth
+                    ),
+                });
+            }
+        }
+        index += 1;
+    }
+
+    let peak = peak.ok_or("no segment has EXT-X-BYTERANGE or EXT-X-BITRATE")?;
+    if peak < lower_limit {
+        failures.push(Failure {
+            index: None,
+            message: format!(
+                "peak segment bit rate is {:.0} bit/s, declared BANDWIDTH is {}",
+                peak, bandwidth
+            ),
+        });
+    }
+
+    Ok(failures)
+}
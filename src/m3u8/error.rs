@@ -0,0 +1,204 @@
+//! Errors produced while parsing an M3U8 playlist.
+//!
+//! Every malformed line is reported as a [`ParseError::Syntax`] carrying the 1-based line
+//! and column of the problem, the raw line as read from the input and a
+//! [`ParseErrorKind`] describing what is wrong with it.
+
+use std::fmt;
+use std::io;
+
+/// Describes what is wrong with a malformed line.
+#[derive(Debug, PartialEq, Clone)]
+pub enum ParseErrorKind {
+    /// The attribute list is not well formed, e.g. an unterminated quoted string.
+    ///
+    /// # Arguments
+    ///
+    /// * `String` - A description of the problem.
+    MalformedAttributeList(String),
+
+    /// An attribute name appears more than once in the same attribute list.
+    ///
+    /// # Arguments
+    ///
+    /// * `String` - The repeated attribute name.
+    DuplicateAttribute(String),
+
+    /// A required attribute is missing.
+    ///
+    /// # Arguments
+    ///
+    /// * `String` - The name of the missing attribute.
+    MissingAttribute(String),
+
+    /// An attribute value has the wrong type or is out of range.
+    InvalidAttributeValue {
+        /// The attribute name.
+        name: String,
+        /// A description of the problem.
+        reason: String,
+    },
+
+    /// The value of a tag that does not use an attribute list is invalid,
+    /// e.g. `#EXT-X-VERSION:999`.
+    ///
+    /// # Arguments
+    ///
+    /// * `String` - A description of the problem.
+    InvalidTagValue(String),
+
+    /// An `EXTINF` or `EXT-X-STREAM-INF` tag is not followed by a URI line.
+    MissingUri,
+
+    /// A URI line is not preceded by an `EXTINF` or `EXT-X-STREAM-INF` tag.
+    UnexpectedUri,
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseErrorKind::MalformedAttributeList(reason) => {
+                write!(f, "malformed attribute list: {}", reason)
+            }
+            ParseErrorKind::DuplicateAttribute(name) => {
+                write!(f, "attribute {} appears more than once", name)
+            }
+            ParseErrorKind::MissingAttribute(name) => {
+                write!(f, "missing required attribute {}", name)
+            }
+            ParseErrorKind::InvalidAttributeValue { name, reason } => {
+                write!(f, "invalid value for attribute {}: {}", name, reason)
+            }
+            ParseErrorKind::InvalidTagValue(reason) => write!(f, "invalid tag value: {}", reason),
+            ParseErrorKind::MissingUri => write!(f, "expected a URI line after this tag"),
+            ParseErrorKind::UnexpectedUri => {
+                write!(f, "URI line is not preceded by EXTINF or EXT-X-STREAM-INF")
+            }
+        }
+    }
+}
+
+/// A `ParseErrorKind` located within a single line, before the line is known.
+///
+/// `offset` is the 0-based byte offset of the problem within the text that was parsed.
+#[derive(Debug, PartialEq, Clone)]
+pub struct LineError {
+    pub offset: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl LineError {
+    /// Creates a new `LineError` at the given offset.
+    pub fn new(offset: usize, kind: ParseErrorKind) -> Self {
+        Self { offset, kind }
+    }
+
+    /// Moves the error `by` bytes to the right, for text parsed from a larger line.
+    pub(crate) fn shifted(self, by: usize) -> Self {
+        Self {
+            offset: self.offset + by,
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for LineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "offset {}: {}", self.offset, self.kind)
+    }
+}
+
+impl std::error::Error for LineError {}
+
+/// An error returned when a typed value, e.g. a `ByteRange`, cannot be parsed.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ParseValueError {
+    /// A description of the expected value, e.g. `byte range`.
+    pub expected: &'static str,
+    /// The text that failed to parse.
+    pub value: String,
+}
+
+impl ParseValueError {
+    /// Creates a new `ParseValueError`.
+    pub fn new(expected: &'static str, value: &str) -> Self {
+        Self {
+            expected,
+            value: value.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ParseValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` is not a valid {}", self.value, self.expected)
+    }
+}
+
+impl std::error::Error for ParseValueError {}
+
+/// An error returned when reading a playlist fails.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The playlist could not be read.
+    Io(io::Error),
+
+    /// A line of the playlist is malformed.
+    Syntax {
+        /// The 1-based line number.
+        line: usize,
+        /// The 1-based column of the problem within the line.
+        column: usize,
+        /// The line as read from the input, without the line terminator.
+        raw: String,
+        /// What is wrong with the line.
+        kind: ParseErrorKind,
+    },
+}
+
+impl ParseError {
+    /// Returns the kind of a syntax error, or `None` for I/O errors.
+    pub fn kind(&self) -> Option<&ParseErrorKind> {
+        match self {
+            ParseError::Io(_) => None,
+            ParseError::Syntax { kind, .. } => Some(kind),
+        }
+    }
+
+    /// Returns the 1-based line number of a syntax error, or `None` for I/O errors.
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            ParseError::Io(_) => None,
+            ParseError::Syntax { line, .. } => Some(*line),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Io(error) => write!(f, "failed to read playlist: {}", error),
+            ParseError::Syntax {
+                line,
+                column,
+                raw,
+                kind,
+            } => write!(f, "line {}, column {}: {}: `{}`", line, column, kind, raw),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseError::Io(error) => Some(error),
+            ParseError::Syntax { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for ParseError {
+    fn from(error: io::Error) -> Self {
+        ParseError::Io(error)
+    }
+}
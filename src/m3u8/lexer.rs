@@ -0,0 +1,97 @@
+//! Line-oriented lexer for M3U8 playlists.
+//!
+//! RFC 8216 Section 4.1 defines a playlist as a sequence of lines, each of which is
+//! either a URI, a blank line, or starts with `#`. Lines starting with `#EXT` are tags,
+//! every other line starting with `#` is a comment.
+//!
+//! The lexer accepts both `LF` and `CRLF` line endings, strips a leading UTF-8 byte
+//! order mark and skips blank lines. It never splits a line on anything other than a
+//! line terminator, so `#` characters inside URIs and quoted attribute values survive.
+//!
+//! # Example
+//!
+//! ```
+//! use m3u8_parser::m3u8::lexer::{Lexer, Line};
+//!
+//! let input = "#EXTM3U\r\n#EXTINF:5.0,\r\nsegment.ts#t=10\r\n";
+//! let lines: Vec<_> = Lexer::new(input).map(|token| token.line).collect();
+//! assert_eq!(
+//!     lines,
+//!     vec![
+//!         Line::Tag("EXTM3U"),
+//!         Line::Tag("EXTINF:5.0,"),
+//!         Line::Uri("segment.ts#t=10"),
+//!     ]
+//! );
+//! ```
+
+/// The UTF-8 byte order mark, which some encoders write at the start of a playlist.
+const BYTE_ORDER_MARK: char = '\u{feff}';
+
+/// A single logical line of a playlist.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Line<'a> {
+    /// A tag line. Holds the text after the leading `#`, e.g. `EXT-X-VERSION:7`.
+    Tag(&'a str),
+    /// A comment line. Holds the text after the leading `#`.
+    Comment(&'a str),
+    /// A URI line identifying a media segment or a playlist.
+    Uri(&'a str),
+}
+
+/// A logical line together with its position in the source.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Token<'a> {
+    /// The 1-based line number.
+    pub number: usize,
+    /// The line as read from the input, without the line terminator.
+    pub raw: &'a str,
+    /// The byte offset of the text held by `line` within `raw`.
+    pub offset: usize,
+    /// The classified line.
+    pub line: Line<'a>,
+}
+
+/// Splits playlist content into logical lines.
+///
+/// Yields a [`Token`] for each non-blank line.
+pub struct Lexer<'a> {
+    lines: std::iter::Enumerate<std::str::Lines<'a>>,
+}
+
+impl<'a> Lexer<'a> {
+    /// Creates a new `Lexer` over the given playlist content.
+    pub fn new(input: &'a str) -> Self {
+        let input = input.strip_prefix(BYTE_ORDER_MARK).unwrap_or(input);
+        Self {
+            lines: input.lines().enumerate(),
+        }
+    }
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (index, raw) in self.lines.by_ref() {
+            let trimmed = raw.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+
+            let indent = raw.len() - raw.trim_start().len();
+            let (line, offset) = match trimmed.strip_prefix('#') {
+                Some(rest) if rest.starts_with("EXT") => (Line::Tag(rest), indent + 1),
+                Some(rest) => (Line::Comment(rest), indent + 1),
+                None => (Line::Uri(trimmed), indent),
+            };
+            return Some(Token {
+                number: index + 1,
+                raw,
+                offset,
+                line,
+            });
+        }
+        None
+    }
+}
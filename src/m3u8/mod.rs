@@ -1,5 +1,9 @@
+pub mod authoring;
+pub mod error;
+pub mod lexer;
 pub mod parser;
 pub mod playlist;
 pub mod tags;
 mod tests;
 pub mod validation;
+pub mod values;
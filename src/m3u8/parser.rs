@@ -1,36 +1,436 @@
-/// Parses attributes from a given input string and returns a map of key-value pairs.
+//! Attribute-list parsing as defined in RFC 8216 Section 4.2.
+//!
+//! Many tags carry an attribute list: a comma-separated list of `NAME=VALUE` pairs.
+//! Values are typed, and quoted strings may contain commas, so the list cannot be split
+//! naively. [`parse_attributes`] tokenizes the list and classifies each value, and the
+//! accessors on [`AttributeList`] check that an attribute has the type a tag expects.
+//!
+//! Errors are reported as [`LineError`]s whose offset points into the parsed input.
+
+use crate::m3u8::error::{LineError, ParseErrorKind, ParseValueError};
+use std::str::FromStr;
+
+/// A typed attribute value (RFC 8216 Section 4.2).
 ///
-/// This function processes an input string formatted as key-value pairs separated by commas.
-/// Each key-value pair is expected to be in the format `key="value"`. The function will trim
-/// the quotes around the values and construct a `HashMap` containing the parsed attributes.
+/// Unquoted values are classified by their lexical form. Since a decimal integer is also
+/// a valid decimal floating-point number, the accessors on [`AttributeList`] accept the
+/// narrower type wherever a wider one is expected.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum AttributeValue {
+    /// An unsigned decimal integer, e.g. `BANDWIDTH=1280000`.
+    DecimalInteger(u64),
+    /// A hexadecimal sequence including its `0x` prefix, e.g. `IV=0x9c7db8778570d05c`.
+    HexadecimalSequence(String),
+    /// A non-negative decimal floating-point number, e.g. `FRAME-RATE=29.970`.
+    DecimalFloatingPoint(f64),
+    /// A negative decimal floating-point number, e.g. `TIME-OFFSET=-12.5`.
+    SignedDecimalFloatingPoint(f64),
+    /// A quoted string, without the surrounding quotes.
+    QuotedString(String),
+    /// An enumerated string, e.g. `METHOD=AES-128` or `DEFAULT=YES`.
+    EnumeratedString(String),
+    /// A decimal resolution, e.g. `RESOLUTION=1920x1080`.
+    DecimalResolution(u64, u64),
+}
+
+impl AttributeValue {
+    /// Classifies an unquoted attribute value by its lexical form.
+    fn from_unquoted(value: &str) -> Result<Self, String> {
+        if value.is_empty() {
+            return Err("empty attribute value".to_string());
+        }
+
+        if let Some(digits) = value
+            .strip_prefix("0x")
+            .or_else(|| value.strip_prefix("0X"))
+        {
+            if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(format!("invalid hexadecimal sequence `{}`", value));
+            }
+            return Ok(AttributeValue::HexadecimalSequence(value.to_string()));
+        }
+
+        if is_decimal_digits(value) {
+            return value
+                .parse()
+                .map(AttributeValue::DecimalInteger)
+                .map_err(|_| format!("decimal integer `{}` is out of range", value));
+        }
+
+        if let Some((width, height)) = value.split_once('x') {
+            if is_decimal_digits(width) && is_decimal_digits(height) {
+                return match (width.parse(), height.parse()) {
+                    (Ok(width), Ok(height)) => Ok(AttributeValue::DecimalResolution(width, height)),
+                    _ => Err(format!("resolution `{}` is out of range", value)),
+                };
+            }
+        }
+
+        let unsigned = value.strip_prefix('-').unwrap_or(value);
+        if let Some((integer, fraction)) = unsigned.split_once('.') {
+            if is_decimal_digits(integer) && fraction.chars().all(|c| c.is_ascii_digit()) {
+                let number: f64 = value
+                    .parse()
+                    .map_err(|_| format!("invalid floating-point number `{}`", value))?;
+                return Ok(if value.starts_with('-') {
+                    AttributeValue::SignedDecimalFloatingPoint(number)
+                } else {
+                    AttributeValue::DecimalFloatingPoint(number)
+                });
+            }
+        }
+        if value.starts_with('-') && is_decimal_digits(unsigned) {
+            let number: f64 = value
+                .parse()
+                .map_err(|_| format!("invalid floating-point number `{}`", value))?;
+            return Ok(AttributeValue::SignedDecimalFloatingPoint(number));
+        }
+
+        if value
+            .chars()
+            .any(|c| c == '"' || c == ',' || c.is_whitespace())
+        {
+            return Err(format!("invalid enumerated string `{}`", value));
+        }
+        Ok(AttributeValue::EnumeratedString(value.to_string()))
+    }
+
+    /// Returns a short description of the value type, used in error messages.
+    fn type_name(&self) -> &'static str {
+        match self {
+            AttributeValue::DecimalInteger(_) => "decimal-integer",
+            AttributeValue::HexadecimalSequence(_) => "hexadecimal-sequence",
+            AttributeValue::DecimalFloatingPoint(_) => "decimal-floating-point",
+            AttributeValue::SignedDecimalFloatingPoint(_) => "signed-decimal-floating-point",
+            AttributeValue::QuotedString(_) => "quoted-string",
+            AttributeValue::EnumeratedString(_) => "enumerated-string",
+            AttributeValue::DecimalResolution(..) => "decimal-resolution",
+        }
+    }
+}
+
+impl std::fmt::Display for AttributeValue {
+    /// Formats the value as it appears in an attribute list, quoting quoted strings.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AttributeValue::DecimalInteger(n) => write!(f, "{}", n),
+            AttributeValue::HexadecimalSequence(s) | AttributeValue::EnumeratedString(s) => {
+                write!(f, "{}", s)
+            }
+            // Integral values keep a fraction so they are not read back as integers.
+            AttributeValue::DecimalFloatingPoint(n)
+            | AttributeValue::SignedDecimalFloatingPoint(n) => {
+                if n.fract() == 0.0 {
+                    write!(f, "{:.1}", n)
+                } else {
+                    write!(f, "{}", n)
+                }
+            }
+            AttributeValue::QuotedString(s) => write!(f, "\"{}\"", s),
+            AttributeValue::DecimalResolution(width, height) => {
+                write!(f, "{}x{}", width, height)
+            }
+        }
+    }
+}
+
+/// A single `NAME=VALUE` pair of an attribute list.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Attribute {
+    /// The attribute name, e.g. `BANDWIDTH`.
+    pub name: String,
+    /// The typed attribute value.
+    pub value: AttributeValue,
+    /// The value as written in the source, without surrounding quotes.
+    pub text: String,
+    /// The byte offset of the attribute name within the parsed input.
+    pub offset: usize,
+}
+
+/// A parsed attribute list, in source order.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct AttributeList {
+    attributes: Vec<Attribute>,
+}
+
+impl AttributeList {
+    /// Returns the attribute with the given name, if present.
+    pub fn get(&self, name: &str) -> Option<&Attribute> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.name == name)
+    }
+
+    /// Returns an iterator over the attributes in source order.
+    pub fn iter(&self) -> std::slice::Iter<'_, Attribute> {
+        self.attributes.iter()
+    }
+
+    /// Returns the number of attributes in the list.
+    pub fn len(&self) -> usize {
+        self.attributes.len()
+    }
+
+    /// Returns `true` if the list contains no attributes.
+    pub fn is_empty(&self) -> bool {
+        self.attributes.is_empty()
+    }
+
+    /// Returns the value of a quoted-string attribute.
+    pub fn quoted_string(&self, name: &str) -> Result<Option<&str>, LineError> {
+        self.typed(name, |value| match value {
+            AttributeValue::QuotedString(s) => Some(s.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Returns the value of an enumerated-string attribute.
+    pub fn enumerated_string(&self, name: &str) -> Result<Option<&str>, LineError> {
+        self.typed(name, |value| match value {
+            AttributeValue::EnumeratedString(s) => Some(s.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Returns the value of a `YES`/`NO` enumerated-string attribute.
+    pub fn yes_no(&self, name: &str) -> Result<Option<bool>, LineError> {
+        match self.enumerated_string(name)? {
+            Some("YES") => Ok(Some(true)),
+            Some("NO") => Ok(Some(false)),
+            Some(other) => Err(LineError::new(
+                self.get(name).map_or(0, |attribute| attribute.offset),
+                ParseErrorKind::InvalidAttributeValue {
+                    name: name.to_string(),
+                    reason: format!("expected YES or NO, found `{}`", other),
+                },
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the value of a decimal-integer attribute.
+    pub fn decimal_integer(&self, name: &str) -> Result<Option<u64>, LineError> {
+        self.typed(name, |value| match value {
+            AttributeValue::DecimalInteger(n) => Some(*n),
+            _ => None,
+        })
+    }
+
+    /// Returns the value of a hexadecimal-sequence attribute, including its `0x` prefix.
+    pub fn hexadecimal_sequence(&self, name: &str) -> Result<Option<&str>, LineError> {
+        self.typed(name, |value| match value {
+            AttributeValue::HexadecimalSequence(s) => Some(s.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Returns the value of a decimal-floating-point attribute.
+    pub fn decimal_float(&self, name: &str) -> Result<Option<f64>, LineError> {
+        self.typed(name, |value| match value {
+            AttributeValue::DecimalInteger(n) => Some(*n as f64),
+            AttributeValue::DecimalFloatingPoint(n) => Some(*n),
+            _ => None,
+        })
+    }
+
+    /// Returns the value of a signed-decimal-floating-point attribute.
+    pub fn signed_decimal_float(&self, name: &str) -> Result<Option<f64>, LineError> {
+        self.typed(name, |value| match value {
+            AttributeValue::DecimalInteger(n) => Some(*n as f64),
+            AttributeValue::DecimalFloatingPoint(n)
+            | AttributeValue::SignedDecimalFloatingPoint(n) => Some(*n),
+            _ => None,
+        })
+    }
+
+    /// Returns the value of a decimal-resolution attribute as `(width, height)`.
+    pub fn resolution(&self, name: &str) -> Result<Option<(u64, u64)>, LineError> {
+        self.typed(name, |value| match value {
+            AttributeValue::DecimalResolution(width, height) => Some((*width, *height)),
+            _ => None,
+        })
+    }
+
+    /// Returns the value of an attribute parsed into a typed value, after `value` has
+    /// checked its type, e.g. `attributes.parse("IV", AttributeList::hexadecimal_sequence)`.
+    pub fn parse<'a, T>(
+        &'a self,
+        name: &str,
+        value: impl FnOnce(&'a Self, &str) -> Result<Option<&'a str>, LineError>,
+    ) -> Result<Option<T>, LineError>
+    where
+        T: FromStr<Err = ParseValueError>,
+    {
+        match value(self, name)? {
+            Some(text) => text.parse().map(Some).map_err(|e: ParseValueError| {
+                LineError::new(
+                    self.get(name).map_or(0, |attribute| attribute.offset),
+                    ParseErrorKind::InvalidAttributeValue {
+                        name: name.to_string(),
+                        reason: e.to_string(),
+                    },
+                )
+            }),
+            None => Ok(None),
+        }
+    }
+
+    /// Looks up an attribute and converts its value with `extract`, reporting an error
+    /// if the attribute exists but has a different type.
+    fn typed<'a, T>(
+        &'a self,
+        name: &str,
+        extract: impl FnOnce(&'a AttributeValue) -> Option<T>,
+    ) -> Result<Option<T>, LineError> {
+        match self.get(name) {
+            Some(attribute) => extract(&attribute.value).map(Some).ok_or_else(|| {
+                LineError::new(
+                    attribute.offset,
+                    ParseErrorKind::InvalidAttributeValue {
+                        name: name.to_string(),
+                        reason: format!("unexpected type {}", attribute.value.type_name()),
+                    },
+                )
+            }),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a AttributeList {
+    type Item = &'a Attribute;
+    type IntoIter = std::slice::Iter<'a, Attribute>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Parses an attribute list (RFC 8216 Section 4.2) into typed values.
+///
+/// Quoted strings may contain commas, attribute names must consist of `A-Z`, `0-9` and
+/// `-`, and an attribute name may appear at most once. Any malformed input is reported
+/// as an error instead of being skipped.
 ///
 /// # Arguments
 ///
-/// * `input` - A string containing the attributes to be parsed. The expected format is
-///   `key1="value1",key2="value2",...`.
+/// * `input` - The attribute list, i.e. everything after the `:` of a tag, e.g.
+///   `BANDWIDTH=1280000,CODECS="avc1.64001f,mp4a.40.2"`.
 ///
 /// # Returns
 ///
-/// A result containing a `HashMap<String, String>` of attributes if parsing is successful,
-/// or an error message as a string if an error occurs during parsing.
+/// A result containing the parsed `AttributeList` in source order, or a `LineError`
+/// locating the first malformed attribute within `input`.
 ///
 /// # Example
 ///
 /// ```
-/// use m3u8_parser::m3u8::parser::parse_attributes;
-/// let input = r#"METHOD="AES-128",URI="https://example.com/key",IV="1234567890abcdef""#;
+/// use m3u8_parser::m3u8::parser::{parse_attributes, AttributeValue};
+/// let input = r#"BANDWIDTH=1280000,CODECS="avc1.64001f,mp4a.40.2",RESOLUTION=1280x720"#;
 /// let attributes = parse_attributes(input).expect("Failed to parse attributes");
-/// assert_eq!(attributes.get("METHOD"), Some(&"AES-128".to_string()));
-/// assert_eq!(attributes.get("URI"), Some(&"https://example.com/key".to_string()));
+/// assert_eq!(attributes.decimal_integer("BANDWIDTH"), Ok(Some(1280000)));
+/// assert_eq!(attributes.quoted_string("CODECS"), Ok(Some("avc1.64001f,mp4a.40.2")));
+/// assert_eq!(
+///     attributes.get("RESOLUTION").map(|a| &a.value),
+///     Some(&AttributeValue::DecimalResolution(1280, 720))
+/// );
 /// ```
 ///
-pub fn parse_attributes(input: &str) -> Result<std::collections::HashMap<String, String>, String> {
-    let mut attributes = std::collections::HashMap::new();
-    for part in input.split(',') {
-        let parts: Vec<&str> = part.splitn(2, '=').collect();
-        if parts.len() == 2 {
-            attributes.insert(parts[0].to_string(), parts[1].trim_matches('"').to_string());
+pub fn parse_attributes(input: &str) -> Result<AttributeList, LineError> {
+    let mut list = AttributeList::default();
+    let mut offset = 0;
+
+    while offset < input.len() {
+        let rest = &input[offset..];
+        let malformed = |at: usize, reason: String| {
+            LineError::new(at, ParseErrorKind::MalformedAttributeList(reason))
+        };
+
+        let (name, after_name) = rest
+            .split_once('=')
+            .ok_or_else(|| malformed(offset, format!("attribute `{}` has no value", rest)))?;
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '-')
+        {
+            return Err(malformed(
+                offset,
+                format!("invalid attribute name `{}`", name),
+            ));
         }
+        if list.get(name).is_some() {
+            return Err(LineError::new(
+                offset,
+                ParseErrorKind::DuplicateAttribute(name.to_string()),
+            ));
+        }
+
+        let value_offset = offset + name.len() + 1;
+        let (value, text, value_len) = if let Some(quoted) = after_name.strip_prefix('"') {
+            let end = quoted.find('"').ok_or_else(|| {
+                malformed(
+                    value_offset,
+                    format!("unterminated quoted string for attribute {}", name),
+                )
+            })?;
+            let text = &quoted[..end];
+            (
+                AttributeValue::QuotedString(text.to_string()),
+                text,
+                end + 2,
+            )
+        } else {
+            let end = after_name.find(',').unwrap_or(after_name.len());
+            let text = &after_name[..end];
+            let value = AttributeValue::from_unquoted(text).map_err(|reason| {
+                LineError::new(
+                    value_offset,
+                    ParseErrorKind::InvalidAttributeValue {
+                        name: name.to_string(),
+                        reason,
+                    },
+                )
+            })?;
+            (value, text, end)
+        };
+
+        list.attributes.push(Attribute {
+            name: name.to_string(),
+            value,
+            text: text.to_string(),
+            offset,
+        });
+
+        let separator = value_offset + value_len;
+        offset = match input[separator..].strip_prefix(',') {
+            Some("") => {
+                return Err(malformed(
+                    separator,
+                    "attribute list ends with a comma".to_string(),
+                ))
+            }
+            Some(_) => separator + 1,
+            None if separator == input.len() => separator,
+            None => {
+                return Err(malformed(
+                    separator,
+                    format!(
+                        "unexpected characters `{}` after attribute {}",
+                        &input[separator..],
+                        name
+                    ),
+                ))
+            }
+        };
     }
-    Ok(attributes)
+
+    Ok(list)
+}
+
+/// Returns `true` if `s` is a non-empty run of ASCII digits.
+fn is_decimal_digits(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_ascii_digit())
 }
@@ -1,13 +1,22 @@
+use crate::m3u8::parser::AttributeValue;
+use crate::m3u8::playlist::media::{Key, Map};
 use crate::m3u8::playlist::Playlist;
 use crate::m3u8::tags::Tag;
 use crate::m3u8::validation::ValidationError;
-use std::cell::RefCell;
-use std::rc::Rc;
+use crate::m3u8::values::{
+    ByteRange, DateTime, Decimal, InitializationVector, KeyMethod, MediaType, PlaylistType,
+    Precision, Resolution, TimeOffset,
+};
 
 /// A builder for creating a `Playlist` with a chained interface.
-#[derive(Clone)]
+///
+/// The builder owns its tags, so it is `Send` and `Sync`, and a clone is an independent
+/// copy that can be continued separately.
+#[derive(Debug, Clone)]
 pub struct PlaylistBuilder {
-    tags: Rc<RefCell<Vec<Tag>>>,
+    tags: Vec<Tag>,
+    auto_version: bool,
+    precision: Precision,
 }
 
 impl Default for PlaylistBuilder {
@@ -20,146 +29,191 @@ impl PlaylistBuilder {
     /// Creates a new `PlaylistBuilder`.
     pub fn new() -> Self {
         Self {
-            tags: Rc::new(RefCell::new(Vec::new())),
+            tags: Vec::new(),
+            auto_version: false,
+            precision: Precision::default(),
+        }
+    }
+
+    /// Creates a `PlaylistBuilder` that starts with the tags of `playlist`, so tags can be
+    /// appended to an existing manifest.
+    pub fn from_playlist(playlist: &Playlist) -> Self {
+        Self {
+            tags: playlist.tags.clone(),
+            ..Self::new()
         }
     }
 
     /// Adds an `ExtM3U` tag.
-    pub fn extm3u(self) -> Self {
-        self.tags.borrow_mut().push(Tag::ExtM3U);
+    pub fn extm3u(mut self) -> Self {
+        self.tags.push(Tag::ExtM3U);
         self
     }
 
     /// Adds an `ExtXVersion` tag.
-    pub fn version(self, version: u8) -> Self {
-        self.tags.borrow_mut().push(Tag::ExtXVersion(version));
+    pub fn version(mut self, version: u8) -> Self {
+        self.tags.push(Tag::ExtXVersion(version));
         self
     }
 
     /// Adds an `ExtInf` tag.
-    pub fn extinf(self, url: &str, duration: f32, title: Option<String>) -> Self {
+    ///
+    /// The duration is stored as the shortest decimal that converts back to it, e.g.
+    /// `5.005`; see `precision`. A duration that is not finite is stored as 0, which
+    /// `build` reports as invalid.
+    pub fn extinf(mut self, url: &str, duration: f32, title: Option<String>) -> Self {
+        let duration = Decimal::from_f32(duration).unwrap_or(Decimal::ZERO);
         self.tags
-            .borrow_mut()
             .push(Tag::ExtInf(url.to_string(), duration, title));
         self
     }
 
     /// Adds an `ExtXTargetDuration` tag.
-    pub fn target_duration(self, duration: u64) -> Self {
-        self.tags
-            .borrow_mut()
-            .push(Tag::ExtXTargetDuration(duration));
+    pub fn target_duration(mut self, duration: u64) -> Self {
+        self.tags.push(Tag::ExtXTargetDuration(duration));
         self
     }
 
     /// Adds an `ExtXMediaSequence` tag.
-    pub fn media_sequence(self, sequence: u64) -> Self {
-        self.tags
-            .borrow_mut()
-            .push(Tag::ExtXMediaSequence(sequence));
+    pub fn media_sequence(mut self, sequence: u64) -> Self {
+        self.tags.push(Tag::ExtXMediaSequence(sequence));
         self
     }
 
     /// Adds an `ExtXDiscontinuitySequence` tag.
-    pub fn discontinuity_sequence(self, sequence: u32) -> Self {
-        self.tags
-            .borrow_mut()
-            .push(Tag::ExtXDiscontinuitySequence(sequence));
+    pub fn discontinuity_sequence(mut self, sequence: u32) -> Self {
+        self.tags.push(Tag::ExtXDiscontinuitySequence(sequence));
         self
     }
 
     /// Adds an `ExtXEndList` tag.
-    pub fn end_list(self) -> Self {
-        self.tags.borrow_mut().push(Tag::ExtXEndList);
+    pub fn end_list(mut self) -> Self {
+        self.tags.push(Tag::ExtXEndList);
         self
     }
 
     /// Adds an `ExtXKey` tag.
     pub fn key(
-        self,
-        method: &str,
+        mut self,
+        method: KeyMethod,
         uri: Option<&str>,
-        iv: Option<&str>,
+        iv: Option<InitializationVector>,
         keyformat: Option<&str>,
         keyformatversions: Option<&str>,
     ) -> Self {
-        self.tags.borrow_mut().push(Tag::ExtXKey {
-            method: method.to_string(),
+        self.tags.push(Tag::ExtXKey {
+            method,
             uri: uri.map(|s| s.to_string()),
-            iv: iv.map(|s| s.to_string()),
+            iv,
             keyformat: keyformat.map(|s| s.to_string()),
             keyformatversions: keyformatversions.map(|s| s.to_string()),
+            other: Vec::new(),
         });
         self
     }
 
     /// Adds an `ExtXMap` tag.
-    pub fn map(self, uri: &str, byterange: Option<&str>) -> Self {
-        self.tags.borrow_mut().push(Tag::ExtXMap {
+    pub fn map(mut self, uri: &str, byterange: Option<ByteRange>) -> Self {
+        self.tags.push(Tag::ExtXMap {
             uri: uri.to_string(),
-            byterange: byterange.map(|s| s.to_string()),
+            byterange,
+            other: Vec::new(),
         });
         self
     }
 
     /// Adds an `ExtXProgramDateTime` tag.
-    pub fn program_date_time(self, date_time: &str) -> Self {
-        self.tags
-            .borrow_mut()
-            .push(Tag::ExtXProgramDateTime(date_time.to_string()));
+    pub fn program_date_time(mut self, date_time: DateTime) -> Self {
+        self.tags.push(Tag::ExtXProgramDateTime(date_time));
+        self
+    }
+
+    /// Adds an `ExtXDateRange` tag.
+    #[allow(clippy::too_many_arguments)]
+    pub fn date_range(
+        mut self,
+        id: &str,
+        class: Option<&str>,
+        start_date: DateTime,
+        end_date: Option<DateTime>,
+        duration: Option<f32>,
+        planned_duration: Option<f32>,
+        end_on_next: Option<bool>,
+        cue: Option<&str>,
+        client_attributes: Vec<(String, AttributeValue)>,
+    ) -> Self {
+        self.tags.push(Tag::ExtXDateRange {
+            id: id.to_string(),
+            class: class.map(|s| s.to_string()),
+            start_date,
+            end_date,
+            duration,
+            planned_duration,
+            end_on_next,
+            cue: cue.map(|s| s.to_string()),
+            client_attributes,
+            other: Vec::new(),
+        });
         self
     }
 
     /// Adds an `ExtXGap` tag.
-    pub fn gap(self) -> Self {
-        self.tags.borrow_mut().push(Tag::ExtXGap);
+    pub fn gap(mut self) -> Self {
+        self.tags.push(Tag::ExtXGap);
         self
     }
 
     /// Adds an `ExtXByteRange` tag.
-    pub fn byte_range(self, byterange: &str) -> Self {
-        self.tags
-            .borrow_mut()
-            .push(Tag::ExtXByteRange(byterange.to_string()));
+    pub fn byte_range(mut self, byterange: ByteRange) -> Self {
+        self.tags.push(Tag::ExtXByteRange(byterange));
         self
     }
 
     /// Adds an `ExtXDefine` tag.
-    pub fn define(self, value: &str) -> Self {
-        self.tags
-            .borrow_mut()
-            .push(Tag::ExtXDefine(value.to_string()));
+    pub fn define(mut self, value: &str) -> Self {
+        self.tags.push(Tag::ExtXDefine(value.to_string()));
         self
     }
 
     /// Adds an `ExtXMedia` tag.
     #[allow(clippy::too_many_arguments)]
     pub fn media(
-        self,
-        type_: &str,
+        mut self,
+        type_: MediaType,
         group_id: &str,
         name: Option<&str>,
         uri: Option<&str>,
         default: Option<bool>,
-        autoplay: Option<bool>,
+        autoselect: Option<bool>,
         characteristics: Option<&str>,
         language: Option<&str>,
         forced: Option<bool>,
         language_codec: Option<&str>,
         instream_id: Option<&str>,
+        assoc_language: Option<&str>,
+        channels: Option<&str>,
+        stable_rendition_id: Option<&str>,
+        bit_depth: Option<u32>,
+        sample_rate: Option<u32>,
     ) -> Self {
-        self.tags.borrow_mut().push(Tag::ExtXMedia {
-            type_: type_.to_string(),
+        self.tags.push(Tag::ExtXMedia {
+            type_,
             group_id: group_id.to_string(),
             name: name.map(|s| s.to_string()),
             uri: uri.map(|s| s.to_string()),
             default,
-            autoplay,
+            autoselect,
             characteristics: characteristics.map(|s| s.to_string()),
             language: language.map(|s| s.to_string()),
             instream_id: instream_id.map(|s| s.to_string()),
             language_codec: language_codec.map(|s| s.to_string()),
             forced,
+            assoc_language: assoc_language.map(|s| s.to_string()),
+            channels: channels.map(|s| s.to_string()),
+            stable_rendition_id: stable_rendition_id.map(|s| s.to_string()),
+            bit_depth,
+            sample_rate,
+            other: Vec::new(),
         });
         self
     }
@@ -167,94 +221,174 @@ impl PlaylistBuilder {
     /// Adds an `ExtXStreamInf` tag.
     #[allow(clippy::too_many_arguments)]
     pub fn stream_inf(
-        self,
+        mut self,
+        uri: &str,
         bandwidth: u32,
         codecs: Option<&str>,
-        resolution: Option<&str>,
+        resolution: Option<Resolution>,
         frame_rate: Option<f32>,
         audio: Option<&str>,
         video: Option<&str>,
         subtitle: Option<&str>,
         closed_captions: Option<&str>,
+        average_bandwidth: Option<u32>,
+        hdcp_level: Option<&str>,
+        video_range: Option<&str>,
+        score: Option<f32>,
+        supplemental_codecs: Option<&str>,
+        allowed_cpc: Option<&str>,
+        stable_variant_id: Option<&str>,
+        pathway_id: Option<&str>,
+        req_video_layout: Option<&str>,
     ) -> Self {
-        self.tags.borrow_mut().push(Tag::ExtXStreamInf {
+        self.tags.push(Tag::ExtXStreamInf {
             bandwidth,
             codecs: codecs.map(|s| s.to_string()),
-            resolution: resolution.map(|s| s.to_string()),
+            resolution,
             frame_rate,
             audio: audio.map(|s| s.to_string()),
             video: video.map(|s| s.to_string()),
             subtitle: subtitle.map(|s| s.to_string()),
             closed_captions: closed_captions.map(|s| s.to_string()),
+            average_bandwidth,
+            hdcp_level: hdcp_level.map(|s| s.to_string()),
+            video_range: video_range.map(|s| s.to_string()),
+            score,
+            supplemental_codecs: supplemental_codecs.map(|s| s.to_string()),
+            allowed_cpc: allowed_cpc.map(|s| s.to_string()),
+            stable_variant_id: stable_variant_id.map(|s| s.to_string()),
+            pathway_id: pathway_id.map(|s| s.to_string()),
+            req_video_layout: req_video_layout.map(|s| s.to_string()),
+            uri: uri.to_string(),
+            other: Vec::new(),
         });
         self
     }
 
     /// Adds an `ExtXIFrameStreamInf` tag.
     pub fn iframe_stream_inf(
-        self,
+        mut self,
         bandwidth: u32,
         codecs: Option<&str>,
-        resolution: Option<&str>,
+        resolution: Option<Resolution>,
         frame_rate: Option<f32>,
         uri: &str,
     ) -> Self {
-        self.tags.borrow_mut().push(Tag::ExtXIFrameStreamInf {
+        self.tags.push(Tag::ExtXIFrameStreamInf {
             bandwidth,
             codecs: codecs.map(|s| s.to_string()),
-            resolution: resolution.map(|s| s.to_string()),
+            resolution,
             frame_rate,
             uri: uri.to_string(),
+            other: Vec::new(),
         });
         self
     }
 
     /// Adds an `ExtXBitrate` tag.
-    pub fn bitrate(self, bitrate: u32) -> Self {
-        self.tags.borrow_mut().push(Tag::ExtXBitrate(bitrate));
+    pub fn bitrate(mut self, bitrate: u32) -> Self {
+        self.tags.push(Tag::ExtXBitrate(bitrate));
         self
     }
 
     /// Adds an `ExtXIndependentSegments` tag.
-    pub fn independent_segments(self) -> Self {
-        self.tags.borrow_mut().push(Tag::ExtXIndependentSegments);
+    pub fn independent_segments(mut self) -> Self {
+        self.tags.push(Tag::ExtXIndependentSegments);
         self
     }
 
     /// Adds an `ExtXStart` tag.
-    pub fn start(self, time_offset: &str, precise: Option<bool>) -> Self {
-        self.tags.borrow_mut().push(Tag::ExtXStart {
-            time_offset: time_offset.to_string(),
+    pub fn start(mut self, time_offset: TimeOffset, precise: Option<bool>) -> Self {
+        self.tags.push(Tag::ExtXStart {
+            time_offset,
             precise,
+            other: Vec::new(),
         });
         self
     }
 
-    /// Adds an `ExtXSessionData` tag.
-    pub fn session_data(self, id: &str, value: &str, language: Option<&str>) -> Self {
-        self.tags.borrow_mut().push(Tag::ExtXSessionData {
+    /// Adds an `ExtXSessionData` tag with a VALUE.
+    pub fn session_data(mut self, id: &str, value: &str, language: Option<&str>) -> Self {
+        self.tags.push(Tag::ExtXSessionData {
             id: id.to_string(),
-            value: value.to_string(),
+            value: Some(value.to_string()),
+            uri: None,
             language: language.map(|s| s.to_string()),
+            other: Vec::new(),
+        });
+        self
+    }
+
+    /// Adds an `ExtXSessionData` tag whose data is loaded from a JSON file at `uri`.
+    pub fn session_data_uri(mut self, id: &str, uri: &str, language: Option<&str>) -> Self {
+        self.tags.push(Tag::ExtXSessionData {
+            id: id.to_string(),
+            value: None,
+            uri: Some(uri.to_string()),
+            language: language.map(|s| s.to_string()),
+            other: Vec::new(),
         });
         self
     }
 
     /// Adds an `ExtXSessionKey` tag.
-    pub fn session_key(self, method: &str, uri: Option<&str>, iv: Option<&str>) -> Self {
-        self.tags.borrow_mut().push(Tag::ExtXSessionKey {
-            method: method.to_string(),
+    pub fn session_key(
+        mut self,
+        method: KeyMethod,
+        uri: Option<&str>,
+        iv: Option<InitializationVector>,
+    ) -> Self {
+        self.tags.push(Tag::ExtXSessionKey {
+            method,
             uri: uri.map(|s| s.to_string()),
-            iv: iv.map(|s| s.to_string()),
+            iv,
+            other: Vec::new(),
         });
         self
     }
 
+    /// Makes `build` declare the version the playlist requires.
+    ///
+    /// An `ExtXVersion` tag lower than `Playlist::required_version` is raised, and one is
+    /// added after `ExtM3U` if the playlist has none.
+    pub fn auto_version(self) -> Self {
+        Self {
+            auto_version: true,
+            ..self
+        }
+    }
+
+    /// Makes `build` write every `ExtInf` duration with the given precision, e.g.
+    /// `Precision::Fixed(3)` for `6.000` and `5.005`.
+    pub fn precision(self, precision: Precision) -> Self {
+        Self { precision, ..self }
+    }
+
     /// Constructs the final `Playlist` and validates it.
     pub fn build(self) -> Result<Playlist, Vec<ValidationError>> {
-        let playlist = Playlist {
-            tags: self.tags.borrow().clone(),
-        };
+        let mut playlist = Playlist { tags: self.tags };
+        for tag in &mut playlist.tags {
+            if let Tag::ExtInf(_, duration, _) = tag {
+                *duration = self.precision.apply(*duration);
+            }
+        }
+        if self.auto_version {
+            let required = playlist.required_version();
+            match playlist
+                .tags
+                .iter_mut()
+                .find(|tag| matches!(tag, Tag::ExtXVersion(_)))
+            {
+                Some(Tag::ExtXVersion(version)) => *version = (*version).max(required),
+                _ => {
+                    let index = match playlist.tags.first() {
+                        Some(Tag::ExtM3U) => 1,
+                        _ => 0,
+                    };
+                    playlist.tags.insert(index, Tag::ExtXVersion(required));
+                }
+            }
+        }
         match playlist.validate() {
             Ok(_) => Ok(playlist),
             Err(errors) => Err(errors),
@@ -262,10 +396,260 @@ impl PlaylistBuilder {
     }
 
     /// Adds an `ExtXPlaylistType` tag.
-    pub fn playlist_type(self, playlist_type: &str) -> Self {
-        self.tags
-            .borrow_mut()
-            .push(Tag::ExtXPlaylistType(playlist_type.to_string()));
+    pub fn playlist_type(mut self, playlist_type: PlaylistType) -> Self {
+        self.tags.push(Tag::ExtXPlaylistType(playlist_type));
         self
     }
 }
+
+/// A media segment for [`MediaPlaylistBuilder::segment`].
+#[derive(Debug, Clone)]
+pub struct SegmentBuilder {
+    uri: String,
+    duration: Decimal,
+    title: Option<String>,
+    byte_range: Option<ByteRange>,
+    key: Option<Key>,
+    map: Option<Map>,
+    discontinuity: bool,
+    program_date_time: Option<DateTime>,
+}
+
+impl SegmentBuilder {
+    /// Creates a segment with the given URI and duration in seconds.
+    pub fn new(uri: &str, duration: Decimal) -> Self {
+        Self {
+            uri: uri.to_string(),
+            duration,
+            title: None,
+            byte_range: None,
+            key: None,
+            map: None,
+            discontinuity: false,
+            program_date_time: None,
+        }
+    }
+
+    /// Sets the title of the `EXTINF` tag.
+    pub fn title(self, title: &str) -> Self {
+        Self {
+            title: Some(title.to_string()),
+            ..self
+        }
+    }
+
+    /// Sets the sub-range of the resource, written as `EXT-X-BYTERANGE`.
+    pub fn byte_range(self, byte_range: ByteRange) -> Self {
+        Self {
+            byte_range: Some(byte_range),
+            ..self
+        }
+    }
+
+    /// Encrypts the segment with `key`. Segments without a key are not encrypted.
+    pub fn key(self, key: Key) -> Self {
+        Self {
+            key: Some(key),
+            ..self
+        }
+    }
+
+    /// Sets the Media Initialization Section of the segment.
+    pub fn map(self, map: Map) -> Self {
+        Self {
+            map: Some(map),
+            ..self
+        }
+    }
+
+    /// Marks a discontinuity before the segment.
+    pub fn discontinuity(self) -> Self {
+        Self {
+            discontinuity: true,
+            ..self
+        }
+    }
+
+    /// Sets the `EXT-X-PROGRAM-DATE-TIME` of the segment.
+    pub fn program_date_time(self, date_time: DateTime) -> Self {
+        Self {
+            program_date_time: Some(date_time),
+            ..self
+        }
+    }
+}
+
+/// A builder for Media Playlists that works with segments instead of tags.
+///
+/// `build` writes the header tags, then each segment with the tags it needs:
+///
+/// - `EXT-X-TARGETDURATION` is the longest segment duration rounded to the nearest
+///   integer, and at least 1, unless set with `target_duration`.
+/// - `EXT-X-KEY` is written where the key differs from the previous segment's, with
+///   `METHOD=NONE` where encryption stops.
+/// - `EXT-X-MAP` is written where the map differs from the previous segment's. A segment
+///   without a map keeps the previous one, as HLS cannot remove it.
+/// - `EXT-X-DISCONTINUITY` is written before segments marked with `discontinuity`.
+/// - `EXT-X-VERSION` is the version the playlist requires, if greater than 1.
+///
+/// # Example
+///
+/// ```
+/// use m3u8_parser::m3u8::playlist::builder::{MediaPlaylistBuilder, SegmentBuilder};
+///
+/// let playlist = MediaPlaylistBuilder::new()
+///     .segment(SegmentBuilder::new("first.ts", "9.009".parse().unwrap()))
+///     .segment(SegmentBuilder::new("second.ts", "4.5".parse().unwrap()).discontinuity())
+///     .end_list()
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(
+///     playlist.to_string(),
+///     "#EXTM3U\n#EXT-X-VERSION:3\n#EXT-X-TARGETDURATION:9\n#EXTINF:9.009,\nfirst.ts\n\
+///      #EXT-X-DISCONTINUITY\n#EXTINF:4.5,\nsecond.ts\n#EXT-X-ENDLIST\n"
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MediaPlaylistBuilder {
+    segments: Vec<SegmentBuilder>,
+    target_duration: Option<u64>,
+    media_sequence: Option<u64>,
+    discontinuity_sequence: Option<u32>,
+    playlist_type: Option<PlaylistType>,
+    independent_segments: bool,
+    end_list: bool,
+}
+
+impl MediaPlaylistBuilder {
+    /// Creates a new `MediaPlaylistBuilder`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a media segment.
+    pub fn segment(mut self, segment: SegmentBuilder) -> Self {
+        self.segments.push(segment);
+        self
+    }
+
+    /// Sets `EXT-X-TARGETDURATION` instead of deriving it from the segments, e.g. to keep
+    /// it constant across reloads of a live playlist. `build` fails if a segment is longer.
+    pub fn target_duration(self, duration: u64) -> Self {
+        Self {
+            target_duration: Some(duration),
+            ..self
+        }
+    }
+
+    /// Sets `EXT-X-MEDIA-SEQUENCE`.
+    pub fn media_sequence(self, sequence: u64) -> Self {
+        Self {
+            media_sequence: Some(sequence),
+            ..self
+        }
+    }
+
+    /// Sets `EXT-X-DISCONTINUITY-SEQUENCE`.
+    pub fn discontinuity_sequence(self, sequence: u32) -> Self {
+        Self {
+            discontinuity_sequence: Some(sequence),
+            ..self
+        }
+    }
+
+    /// Sets `EXT-X-PLAYLIST-TYPE`.
+    pub fn playlist_type(self, playlist_type: PlaylistType) -> Self {
+        Self {
+            playlist_type: Some(playlist_type),
+            ..self
+        }
+    }
+
+    /// Adds `EXT-X-INDEPENDENT-SEGMENTS`.
+    pub fn independent_segments(self) -> Self {
+        Self {
+            independent_segments: true,
+            ..self
+        }
+    }
+
+    /// Adds `EXT-X-ENDLIST` after the last segment.
+    pub fn end_list(self) -> Self {
+        Self {
+            end_list: true,
+            ..self
+        }
+    }
+
+    /// Constructs the `Playlist` and validates it.
+    pub fn build(self) -> Result<Playlist, Vec<ValidationError>> {
+        let target_duration = self.target_duration.unwrap_or_else(|| {
+            self.segments
+                .iter()
+                .filter_map(|segment| segment.duration.round(0))
+                .filter_map(|duration| u64::try_from(duration.value()).ok())
+                .max()
+                .unwrap_or(0)
+                .max(1)
+        });
+
+        let mut tags = vec![Tag::ExtM3U, Tag::ExtXTargetDuration(target_duration)];
+        if let Some(sequence) = self.media_sequence {
+            tags.push(Tag::ExtXMediaSequence(sequence));
+        }
+        if let Some(sequence) = self.discontinuity_sequence {
+            tags.push(Tag::ExtXDiscontinuitySequence(sequence));
+        }
+        if let Some(playlist_type) = self.playlist_type {
+            tags.push(Tag::ExtXPlaylistType(playlist_type));
+        }
+        if self.independent_segments {
+            tags.push(Tag::ExtXIndependentSegments);
+        }
+
+        // The key and map in effect after the previous segment.
+        let mut key: Option<Key> = None;
+        let mut map: Option<Map> = None;
+        for segment in self.segments {
+            if segment.discontinuity {
+                tags.push(Tag::ExtXDiscontinuity);
+            }
+            if segment.key != key {
+                tags.push(match &segment.key {
+                    Some(key) => Tag::from(key),
+                    None => Tag::ExtXKey {
+                        method: KeyMethod::None,
+                        uri: None,
+                        iv: None,
+                        keyformat: None,
+                        keyformatversions: None,
+                        other: Vec::new(),
+                    },
+                });
+                key = segment.key;
+            }
+            if segment.map.is_some() && segment.map != map {
+                tags.extend(segment.map.as_ref().map(Tag::from));
+                map = segment.map;
+            }
+            if let Some(date_time) = segment.program_date_time {
+                tags.push(Tag::ExtXProgramDateTime(date_time));
+            }
+            if let Some(byte_range) = segment.byte_range {
+                tags.push(Tag::ExtXByteRange(byte_range));
+            }
+            tags.push(Tag::ExtInf(segment.uri, segment.duration, segment.title));
+        }
+        if self.end_list {
+            tags.push(Tag::ExtXEndList);
+        }
+
+        let mut playlist = Playlist { tags };
+        let version = playlist.required_version();
+        if version > 1 {
+            playlist.tags.insert(1, Tag::ExtXVersion(version));
+        }
+        playlist.validate().map(|_| playlist)
+    }
+}
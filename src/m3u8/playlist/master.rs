@@ -0,0 +1,374 @@
+//! A typed view of a Master Playlist.
+//!
+//! [`MasterPlaylist`] collects the `EXT-X-STREAM-INF`, `EXT-X-MEDIA` and
+//! `EXT-X-I-FRAME-STREAM-INF` tags of a [`Playlist`] into [`Variant`], [`Rendition`] and
+//! [`IFrameVariant`] structs. All other tags are kept in `tags`.
+//!
+//! The order in which the tags appeared is remembered, so converting a `MasterPlaylist`
+//! back into a `Playlist` reproduces the original tags in their original order. Items
+//! added afterwards are written after the original ones.
+//!
+//! # Example
+//!
+//! ```
+//! use m3u8_parser::m3u8::playlist::master::MasterPlaylist;
+//! use m3u8_parser::m3u8::playlist::Playlist;
+//!
+//! let data = "#EXTM3U
+//! #EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"aac\",NAME=\"English\",URI=\"en.m3u8\"
+//! #EXT-X-STREAM-INF:BANDWIDTH=1280000,AUDIO=\"aac\"
+//! low.m3u8
+//! ";
+//! let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+//! let master = MasterPlaylist::from(&playlist);
+//!
+//! assert_eq!(master.variants[0].audio.as_deref(), Some("aac"));
+//! assert_eq!(master.renditions[0].name.as_deref(), Some("English"));
+//! assert_eq!(Playlist::from(&master), playlist);
+//! ```
+
+use crate::m3u8::parser::AttributeValue;
+use crate::m3u8::playlist::Playlist;
+use crate::m3u8::tags::Tag;
+use crate::m3u8::values::{MediaType, Resolution};
+
+/// A variant stream, from `EXT-X-STREAM-INF` and the URI line that follows it.
+#[derive(Debug, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub struct Variant {
+    pub uri: String,
+    pub bandwidth: u32,
+    pub average_bandwidth: Option<u32>,
+    pub codecs: Option<String>,
+    pub supplemental_codecs: Option<String>,
+    pub resolution: Option<Resolution>,
+    pub frame_rate: Option<f32>,
+    pub hdcp_level: Option<String>,
+    pub video_range: Option<String>,
+    pub score: Option<f32>,
+    pub allowed_cpc: Option<String>,
+    pub stable_variant_id: Option<String>,
+    pub pathway_id: Option<String>,
+    pub req_video_layout: Option<String>,
+    pub audio: Option<String>,
+    pub video: Option<String>,
+    #[cfg_attr(feature = "serde", serde(rename = "subtitles"))]
+    pub subtitle: Option<String>,
+    /// The closed-captions group ID, or `NONE` if the variant has no closed captions.
+    pub closed_captions: Option<String>,
+    /// Attributes this crate does not model, in source order.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub other: Vec<(String, AttributeValue)>,
+}
+
+/// An alternative rendition, from `EXT-X-MEDIA`.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub struct Rendition {
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    pub type_: MediaType,
+    pub group_id: String,
+    pub name: Option<String>,
+    pub uri: Option<String>,
+    pub language: Option<String>,
+    pub assoc_language: Option<String>,
+    pub stable_rendition_id: Option<String>,
+    pub default: Option<bool>,
+    pub autoselect: Option<bool>,
+    pub forced: Option<bool>,
+    pub instream_id: Option<String>,
+    pub bit_depth: Option<u32>,
+    pub sample_rate: Option<u32>,
+    pub characteristics: Option<String>,
+    pub channels: Option<String>,
+    pub language_codec: Option<String>,
+    /// Attributes this crate does not model, in source order.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub other: Vec<(String, AttributeValue)>,
+}
+
+/// An I-frame variant stream, from `EXT-X-I-FRAME-STREAM-INF`.
+#[derive(Debug, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub struct IFrameVariant {
+    pub uri: String,
+    pub bandwidth: u32,
+    pub codecs: Option<String>,
+    pub resolution: Option<Resolution>,
+    pub frame_rate: Option<f32>,
+    /// Attributes this crate does not model, in source order.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub other: Vec<(String, AttributeValue)>,
+}
+
+/// Where an item of a `MasterPlaylist` appeared in the source playlist.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Slot {
+    Tag,
+    Variant,
+    Rendition,
+    IFrameVariant,
+}
+
+/// A Master Playlist.
+///
+/// Two master playlists are equal if their items are, regardless of the order in which
+/// those items appeared in the source playlist.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub struct MasterPlaylist {
+    /// The variant streams, in playlist order.
+    pub variants: Vec<Variant>,
+    /// The alternative renditions, in playlist order.
+    pub renditions: Vec<Rendition>,
+    /// The I-frame variant streams, in playlist order.
+    pub iframe_variants: Vec<IFrameVariant>,
+    /// All other tags, e.g. `EXTM3U`, `EXT-X-SESSION-DATA` and comments, in playlist order.
+    pub tags: Vec<Tag>,
+    /// The order in which the items above appeared in the source playlist. Not
+    /// serialized: a deserialized `MasterPlaylist` writes its items like one built by hand.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    layout: Vec<Slot>,
+}
+
+impl PartialEq for MasterPlaylist {
+    fn eq(&self, other: &Self) -> bool {
+        self.variants == other.variants
+            && self.renditions == other.renditions
+            && self.iframe_variants == other.iframe_variants
+            && self.tags == other.tags
+    }
+}
+
+impl MasterPlaylist {
+    /// Returns the renditions that belong to the given `GROUP-ID`.
+    pub fn group<'a>(&'a self, group_id: &'a str) -> impl Iterator<Item = &'a Rendition> {
+        self.renditions
+            .iter()
+            .filter(move |rendition| rendition.group_id == group_id)
+    }
+}
+
+impl From<&Playlist> for MasterPlaylist {
+    fn from(playlist: &Playlist) -> Self {
+        let mut master = MasterPlaylist::default();
+        for tag in &playlist.tags {
+            let slot = match tag.clone() {
+                Tag::ExtXStreamInf {
+                    bandwidth,
+                    codecs,
+                    resolution,
+                    frame_rate,
+                    audio,
+                    video,
+                    subtitle,
+                    closed_captions,
+                    average_bandwidth,
+                    hdcp_level,
+                    video_range,
+                    score,
+                    supplemental_codecs,
+                    allowed_cpc,
+                    stable_variant_id,
+                    pathway_id,
+                    req_video_layout,
+                    uri,
+                    other,
+                } => {
+                    master.variants.push(Variant {
+                        uri,
+                        bandwidth,
+                        average_bandwidth,
+                        codecs,
+                        supplemental_codecs,
+                        resolution,
+                        frame_rate,
+                        hdcp_level,
+                        video_range,
+                        score,
+                        allowed_cpc,
+                        stable_variant_id,
+                        pathway_id,
+                        req_video_layout,
+                        audio,
+                        video,
+                        subtitle,
+                        closed_captions,
+                        other,
+                    });
+                    Slot::Variant
+                }
+                Tag::ExtXMedia {
+                    type_,
+                    group_id,
+                    name,
+                    uri,
+                    default,
+                    autoselect,
+                    characteristics,
+                    language,
+                    instream_id,
+                    language_codec,
+                    forced,
+                    assoc_language,
+                    channels,
+                    stable_rendition_id,
+                    bit_depth,
+                    sample_rate,
+                    other,
+                } => {
+                    master.renditions.push(Rendition {
+                        type_,
+                        group_id,
+                        name,
+                        uri,
+                        language,
+                        assoc_language,
+                        stable_rendition_id,
+                        default,
+                        autoselect,
+                        forced,
+                        instream_id,
+                        bit_depth,
+                        sample_rate,
+                        characteristics,
+                        channels,
+                        language_codec,
+                        other,
+                    });
+                    Slot::Rendition
+                }
+                Tag::ExtXIFrameStreamInf {
+                    bandwidth,
+                    codecs,
+                    resolution,
+                    frame_rate,
+                    uri,
+                    other,
+                } => {
+                    master.iframe_variants.push(IFrameVariant {
+                        uri,
+                        bandwidth,
+                        codecs,
+                        resolution,
+                        frame_rate,
+                        other,
+                    });
+                    Slot::IFrameVariant
+                }
+                tag => {
+                    master.tags.push(tag);
+                    Slot::Tag
+                }
+            };
+            master.layout.push(slot);
+        }
+        master
+    }
+}
+
+impl From<&Variant> for Tag {
+    fn from(variant: &Variant) -> Self {
+        let variant = variant.clone();
+        Tag::ExtXStreamInf {
+            bandwidth: variant.bandwidth,
+            codecs: variant.codecs,
+            resolution: variant.resolution,
+            frame_rate: variant.frame_rate,
+            audio: variant.audio,
+            video: variant.video,
+            subtitle: variant.subtitle,
+            closed_captions: variant.closed_captions,
+            average_bandwidth: variant.average_bandwidth,
+            hdcp_level: variant.hdcp_level,
+            video_range: variant.video_range,
+            score: variant.score,
+            supplemental_codecs: variant.supplemental_codecs,
+            allowed_cpc: variant.allowed_cpc,
+            stable_variant_id: variant.stable_variant_id,
+            pathway_id: variant.pathway_id,
+            req_video_layout: variant.req_video_layout,
+            uri: variant.uri,
+            other: variant.other,
+        }
+    }
+}
+
+impl From<&Rendition> for Tag {
+    fn from(rendition: &Rendition) -> Self {
+        let rendition = rendition.clone();
+        Tag::ExtXMedia {
+            type_: rendition.type_,
+            group_id: rendition.group_id,
+            name: rendition.name,
+            uri: rendition.uri,
+            default: rendition.default,
+            autoselect: rendition.autoselect,
+            characteristics: rendition.characteristics,
+            language: rendition.language,
+            instream_id: rendition.instream_id,
+            language_codec: rendition.language_codec,
+            forced: rendition.forced,
+            assoc_language: rendition.assoc_language,
+            channels: rendition.channels,
+            stable_rendition_id: rendition.stable_rendition_id,
+            bit_depth: rendition.bit_depth,
+            sample_rate: rendition.sample_rate,
+            other: rendition.other,
+        }
+    }
+}
+
+impl From<&IFrameVariant> for Tag {
+    fn from(variant: &IFrameVariant) -> Self {
+        let variant = variant.clone();
+        Tag::ExtXIFrameStreamInf {
+            bandwidth: variant.bandwidth,
+            codecs: variant.codecs,
+            resolution: variant.resolution,
+            frame_rate: variant.frame_rate,
+            uri: variant.uri,
+            other: variant.other,
+        }
+    }
+}
+
+impl From<&MasterPlaylist> for Playlist {
+    fn from(master: &MasterPlaylist) -> Self {
+        let mut tags_iter = master.tags.iter().cloned();
+        let mut variants = master.variants.iter().map(Tag::from);
+        let mut renditions = master.renditions.iter().map(Tag::from);
+        let mut iframe_variants = master.iframe_variants.iter().map(Tag::from);
+
+        let mut tags: Vec<Tag> = master
+            .layout
+            .iter()
+            .filter_map(|slot| match slot {
+                Slot::Tag => tags_iter.next(),
+                Slot::Variant => variants.next(),
+                Slot::Rendition => renditions.next(),
+                Slot::IFrameVariant => iframe_variants.next(),
+            })
+            .collect();
+
+        // Items added after the conversion from a `Playlist` have no slot yet.
+        tags.extend(tags_iter);
+        tags.extend(renditions);
+        tags.extend(variants);
+        tags.extend(iframe_variants);
+        Playlist { tags }
+    }
+}
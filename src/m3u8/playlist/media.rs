@@ -0,0 +1,514 @@
+//! A typed view of a Media Playlist.
+//!
+//! [`MediaPlaylist`] groups the flat tag vector of a [`Playlist`] into media segments and
+//! resolves which `EXT-X-KEY`, `EXT-X-MAP`, `EXT-X-BYTERANGE` and
+//! `EXT-X-PROGRAM-DATE-TIME` tags apply to each of them.
+//!
+//! Every tag of the source playlist is kept, so converting an unmodified `MediaPlaylist`
+//! back into a `Playlist` reproduces the original tags in their original order. Edits to
+//! the typed fields of a [`Segment`] are written back as tags.
+//!
+//! [`MediaPlaylist::timeline`] maps every segment to a wall-clock time from its
+//! `EXT-X-PROGRAM-DATE-TIME` tags.
+//!
+//! # Example
+//!
+//! ```
+//! use m3u8_parser::m3u8::playlist::media::MediaPlaylist;
+//! use m3u8_parser::m3u8::playlist::Playlist;
+//!
+//! let data = "#EXTM3U
+//! #EXT-X-TARGETDURATION:10
+//! #EXT-X-KEY:METHOD=AES-128,URI=\"key.bin\"
+//! #EXTINF:9.009,
+//! first.ts
+//! #EXTINF:9.009,
+//! second.ts
+//! #EXT-X-ENDLIST
+//! ";
+//! let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+//! let media = MediaPlaylist::from(&playlist);
+//!
+//! assert_eq!(media.segments.len(), 2);
+//! assert_eq!(media.segments[1].key.as_ref().unwrap().uri.as_deref(), Some("key.bin"));
+//! assert_eq!(Playlist::from(&media), playlist);
+//! ```
+
+use crate::m3u8::parser::AttributeValue;
+use crate::m3u8::playlist::Playlist;
+use crate::m3u8::tags::Tag;
+use crate::m3u8::values::{ByteRange, DateTime, Decimal, InitializationVector, KeyMethod};
+use std::time::Duration;
+
+/// The encryption key that applies to a media segment.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub struct Key {
+    pub method: KeyMethod,
+    pub uri: Option<String>,
+    pub iv: Option<InitializationVector>,
+    pub keyformat: Option<String>,
+    pub keyformatversions: Option<String>,
+    /// Attributes this crate does not model, in source order.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub other: Vec<(String, AttributeValue)>,
+}
+
+/// The Media Initialization Section that applies to a media segment.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub struct Map {
+    pub uri: String,
+    pub byterange: Option<ByteRange>,
+    /// Attributes this crate does not model, in source order.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub other: Vec<(String, AttributeValue)>,
+}
+
+/// A partial segment announced by `EXT-X-PART`.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub struct Part {
+    pub uri: String,
+    pub duration: Option<f32>,
+    pub independent: Option<bool>,
+    /// Attributes this crate does not model, in source order.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub other: Vec<(String, AttributeValue)>,
+}
+
+/// A media segment.
+///
+/// `uri`, `duration` and `title` are written back to the `EXTINF` tag when the segment is
+/// converted into tags. All other tags of the segment are kept, in order, in `tags`; the
+/// remaining fields are resolved from those tags (and, for `key` and `map`, from the
+/// segments before it) when the `MediaPlaylist` is created.
+///
+/// When the segment is converted back into tags, the tags behind a field that still
+/// resolves to the same value are kept as they are, including repeated tags such as two
+/// `EXT-X-BYTERANGE` tags. For a field that was changed, tags that disagree with it are
+/// dropped, and a tag for the field is added before the `EXTINF` tag. A key set to `None` is written as `METHOD=NONE`; a map cannot be unset, so
+/// setting `map` to `None` keeps the map of the segments before it in effect.
+#[derive(Debug, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub struct Segment {
+    /// The URI of the segment.
+    pub uri: String,
+    /// The duration of the segment in seconds.
+    pub duration: Decimal,
+    /// The optional title from the `EXTINF` tag.
+    pub title: Option<String>,
+    /// The sub-range of the resource, from `EXT-X-BYTERANGE`.
+    pub byte_range: Option<ByteRange>,
+    /// The key in effect for this segment, or `None` if it is not encrypted.
+    ///
+    /// When several `EXT-X-KEY` tags are in effect, e.g. one per `KEYFORMAT`, the last
+    /// one is used.
+    pub key: Option<Key>,
+    /// The Media Initialization Section in effect for this segment.
+    pub map: Option<Map>,
+    /// Whether the segment is preceded by `EXT-X-DISCONTINUITY`.
+    pub discontinuity: bool,
+    /// The `EXT-X-PROGRAM-DATE-TIME` attached to this segment.
+    pub program_date_time: Option<DateTime>,
+    /// The partial segments that make up this segment.
+    pub parts: Vec<Part>,
+    /// The tags between the previous segment and this segment's `EXTINF`, in order.
+    pub tags: Vec<Tag>,
+}
+
+impl Segment {
+    /// Returns the `EXTINF` tag describing this segment.
+    pub fn extinf(&self) -> Tag {
+        Tag::ExtInf(self.uri.clone(), self.duration, self.title.clone())
+    }
+
+    /// Returns the tags to write before the `EXTINF` tag: `tags`, rewritten to match the
+    /// resolved fields. `key` and `map` are the key and map in effect before the segment,
+    /// and are updated to those in effect after it.
+    fn write_tags(&self, key: &mut Option<Key>, map: &mut Option<Map>) -> Vec<Tag> {
+        let tag_key = self.tags.iter().rev().find_map(key_of);
+        let keep_keys = *tag_key.as_ref().unwrap_or(key) == self.key;
+        let tag_map = self.tags.iter().rev().find_map(map_of);
+        let keep_maps = tag_map.as_ref().or(map.as_ref()) == self.map.as_ref();
+        let keep_parts = self
+            .tags
+            .iter()
+            .filter_map(part_of)
+            .eq(self.parts.iter().cloned());
+        let keep_byte_ranges = self.tags.iter().rev().find_map(|tag| match tag {
+            Tag::ExtXByteRange(range) => Some(*range),
+            _ => None,
+        }) == self.byte_range;
+        let keep_date_times = self.tags.iter().rev().find_map(|tag| match tag {
+            Tag::ExtXProgramDateTime(date_time) => Some(date_time),
+            _ => None,
+        }) == self.program_date_time.as_ref();
+
+        let mut tags: Vec<Tag> = self
+            .tags
+            .iter()
+            .filter(|tag| match tag {
+                Tag::ExtXKey { .. } => keep_keys,
+                Tag::ExtXMap { .. } => keep_maps,
+                Tag::ExtXPart { .. } => keep_parts,
+                Tag::ExtXByteRange(range) => keep_byte_ranges || self.byte_range == Some(*range),
+                Tag::ExtXDiscontinuity => self.discontinuity,
+                Tag::ExtXProgramDateTime(date_time) => {
+                    keep_date_times || self.program_date_time.as_ref() == Some(date_time)
+                }
+                _ => true,
+            })
+            .cloned()
+            .collect();
+
+        if self.discontinuity && !tags.contains(&Tag::ExtXDiscontinuity) {
+            tags.push(Tag::ExtXDiscontinuity);
+        }
+        if !keep_keys && *key != self.key {
+            tags.push(self.key.as_ref().map_or_else(
+                || Tag::ExtXKey {
+                    method: KeyMethod::None,
+                    uri: None,
+                    iv: None,
+                    keyformat: None,
+                    keyformatversions: None,
+                    other: Vec::new(),
+                },
+                Tag::from,
+            ));
+        }
+        if let Some(new_map) = self.map.as_ref().filter(|_| !keep_maps) {
+            if map.as_ref() != Some(new_map) {
+                tags.push(Tag::from(new_map));
+            }
+        }
+        if let Some(date_time) = &self.program_date_time {
+            let tag = Tag::ExtXProgramDateTime(date_time.clone());
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        if let Some(range) = self.byte_range {
+            if !tags.contains(&Tag::ExtXByteRange(range)) {
+                tags.push(Tag::ExtXByteRange(range));
+            }
+        }
+        if !keep_parts {
+            tags.extend(self.parts.iter().map(Tag::from));
+        }
+
+        *key = self.key.clone();
+        if let Some(new_map) = tags.iter().rev().find_map(map_of) {
+            *map = Some(new_map);
+        }
+        tags
+    }
+}
+
+/// A Media Playlist, grouped into segments.
+#[derive(Debug, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub struct MediaPlaylist {
+    /// The tags before the first media segment tag, e.g. `EXTM3U` and
+    /// `EXT-X-TARGETDURATION`.
+    pub header: Vec<Tag>,
+    /// The media segments, in playlist order.
+    pub segments: Vec<Segment>,
+    /// The tags after the last segment, e.g. `EXT-X-ENDLIST`, or the parts and preload
+    /// hints of a segment that is still being produced.
+    pub trailer: Vec<Tag>,
+}
+
+impl MediaPlaylist {
+    /// Returns the value of `EXT-X-TARGETDURATION`, if present.
+    pub fn target_duration(&self) -> Option<u64> {
+        self.header.iter().find_map(|tag| match tag {
+            Tag::ExtXTargetDuration(duration) => Some(*duration),
+            _ => None,
+        })
+    }
+
+    /// Returns the media sequence number of the first segment, which defaults to 0.
+    pub fn media_sequence(&self) -> u64 {
+        self.header
+            .iter()
+            .find_map(|tag| match tag {
+                Tag::ExtXMediaSequence(sequence) => Some(*sequence),
+                _ => None,
+            })
+            .unwrap_or(0)
+    }
+
+    /// Returns `true` if the playlist contains `EXT-X-ENDLIST`.
+    pub fn is_ended(&self) -> bool {
+        self.trailer
+            .iter()
+            .any(|tag| matches!(tag, Tag::ExtXEndList))
+    }
+
+    /// Returns the total duration of all segments in seconds. The sum is exact, or `None`
+    /// if it does not fit in a `Decimal`.
+    pub fn duration(&self) -> Option<Decimal> {
+        self.segments
+            .iter()
+            .try_fold(Decimal::ZERO, |total, segment| {
+                total.checked_add(segment.duration)
+            })
+    }
+
+    /// Returns the wall-clock start time of every segment.
+    ///
+    /// The time of the last `EXT-X-PROGRAM-DATE-TIME` is carried forward through the
+    /// `EXTINF` durations of the segments after it. Where a segment has its own
+    /// `EXT-X-PROGRAM-DATE-TIME` that differs from the carried time by more than
+    /// `tolerance`, the segment is reported in `Timeline::mismatches`, and its own time is
+    /// carried forward from there. Segments after `EXT-X-DISCONTINUITY` may jump in time
+    /// and are not reported.
+    pub fn timeline(&self, tolerance: Duration) -> Timeline {
+        let mut timeline = Timeline::default();
+        let mut next: Option<DateTime> = None;
+
+        for (index, segment) in self.segments.iter().enumerate() {
+            let start = match (&segment.program_date_time, next) {
+                (Some(actual), Some(expected)) => {
+                    let difference = (actual.unix_nanos() - expected.unix_nanos()).unsigned_abs();
+                    if !segment.discontinuity && difference > tolerance.as_nanos() {
+                        timeline.mismatches.push(TimelineMismatch {
+                            segment: index,
+                            expected,
+                            actual: actual.clone(),
+                        });
+                    }
+                    Some(actual.clone())
+                }
+                (actual, expected) => actual.clone().or(expected),
+            };
+            next = start
+                .as_ref()
+                .map(|start| start.add_seconds(segment.duration));
+            timeline.start_times.push(start);
+        }
+
+        timeline
+    }
+}
+
+/// The wall-clock times of the segments of a Media Playlist, from
+/// [`MediaPlaylist::timeline`].
+#[derive(Debug, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub struct Timeline {
+    /// The start time of each segment, in the order of `MediaPlaylist::segments`. `None`
+    /// for segments before the first `EXT-X-PROGRAM-DATE-TIME`.
+    pub start_times: Vec<Option<DateTime>>,
+    /// The segments whose `EXT-X-PROGRAM-DATE-TIME` differs from the carried time.
+    pub mismatches: Vec<TimelineMismatch>,
+}
+
+/// A segment whose `EXT-X-PROGRAM-DATE-TIME` differs from the time carried forward from
+/// the segments before it.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub struct TimelineMismatch {
+    /// The index of the segment in `MediaPlaylist::segments`.
+    pub segment: usize,
+    /// The start time carried forward from the segments before it.
+    pub expected: DateTime,
+    /// The start time given by its `EXT-X-PROGRAM-DATE-TIME`.
+    pub actual: DateTime,
+}
+
+impl TimelineMismatch {
+    /// Returns `true` if the segment starts after the previous one ends.
+    pub fn is_gap(&self) -> bool {
+        self.actual > self.expected
+    }
+
+    /// Returns `true` if the segment starts before the previous one ends.
+    pub fn is_overlap(&self) -> bool {
+        self.actual < self.expected
+    }
+}
+
+/// Returns `true` for tags that belong to the following media segment.
+fn is_segment_tag(tag: &Tag) -> bool {
+    matches!(
+        tag,
+        Tag::ExtInf(..)
+            | Tag::ExtXByteRange(_)
+            | Tag::ExtXDiscontinuity
+            | Tag::ExtXKey { .. }
+            | Tag::ExtXMap { .. }
+            | Tag::ExtXProgramDateTime(_)
+            | Tag::ExtXDateRange { .. }
+            | Tag::ExtXGap
+            | Tag::ExtXBitrate(_)
+            | Tag::ExtXPart { .. }
+    )
+}
+
+/// Returns the key an `EXT-X-KEY` tag puts into effect, `Some(None)` for `METHOD=NONE`, or
+/// `None` for other tags.
+fn key_of(tag: &Tag) -> Option<Option<Key>> {
+    match tag {
+        Tag::ExtXKey {
+            method,
+            uri,
+            iv,
+            keyformat,
+            keyformatversions,
+            other,
+        } => Some((*method != KeyMethod::None).then(|| Key {
+            method: method.clone(),
+            uri: uri.clone(),
+            iv: *iv,
+            keyformat: keyformat.clone(),
+            keyformatversions: keyformatversions.clone(),
+            other: other.clone(),
+        })),
+        _ => None,
+    }
+}
+
+/// Returns the map an `EXT-X-MAP` tag puts into effect, or `None` for other tags.
+fn map_of(tag: &Tag) -> Option<Map> {
+    match tag {
+        Tag::ExtXMap {
+            uri,
+            byterange,
+            other,
+        } => Some(Map {
+            uri: uri.clone(),
+            byterange: *byterange,
+            other: other.clone(),
+        }),
+        _ => None,
+    }
+}
+
+/// Returns the partial segment an `EXT-X-PART` tag announces, or `None` for other tags.
+fn part_of(tag: &Tag) -> Option<Part> {
+    match tag {
+        Tag::ExtXPart {
+            uri,
+            duration,
+            independent,
+            other,
+        } => Some(Part {
+            uri: uri.clone(),
+            duration: *duration,
+            independent: *independent,
+            other: other.clone(),
+        }),
+        _ => None,
+    }
+}
+
+impl From<&Playlist> for MediaPlaylist {
+    fn from(playlist: &Playlist) -> Self {
+        let mut media = MediaPlaylist::default();
+        let mut tags = playlist.tags.iter().peekable();
+
+        while let Some(tag) = tags.next_if(|tag| !is_segment_tag(tag)) {
+            media.header.push(tag.clone());
+        }
+
+        // Keys and maps stay in effect until the next tag of the same kind.
+        let mut key: Option<Key> = None;
+        let mut map: Option<Map> = None;
+        let mut segment = Segment::default();
+
+        for tag in tags {
+            match tag {
+                Tag::ExtInf(uri, duration, title) => {
+                    segment.uri = uri.clone();
+                    segment.duration = *duration;
+                    segment.title = title.clone();
+                    segment.key = key.clone();
+                    segment.map = map.clone();
+                    media.segments.push(std::mem::take(&mut segment));
+                    continue;
+                }
+                Tag::ExtXKey { .. } => key = key_of(tag).flatten(),
+                Tag::ExtXMap { .. } => map = map_of(tag),
+                Tag::ExtXByteRange(range) => segment.byte_range = Some(*range),
+                Tag::ExtXDiscontinuity => segment.discontinuity = true,
+                Tag::ExtXProgramDateTime(date_time) => {
+                    segment.program_date_time = Some(date_time.clone())
+                }
+                Tag::ExtXPart { .. } => segment.parts.extend(part_of(tag)),
+                _ => {}
+            }
+            segment.tags.push(tag.clone());
+        }
+
+        media.trailer = segment.tags;
+        media
+    }
+}
+
+impl From<&MediaPlaylist> for Playlist {
+    fn from(media: &MediaPlaylist) -> Self {
+        let mut tags = media.header.clone();
+        let mut key = None;
+        let mut map = None;
+        for segment in &media.segments {
+            tags.extend(segment.write_tags(&mut key, &mut map));
+            tags.push(segment.extinf());
+        }
+        tags.extend(media.trailer.iter().cloned());
+        Playlist { tags }
+    }
+}
+
+impl From<&Key> for Tag {
+    fn from(key: &Key) -> Self {
+        let key = key.clone();
+        Tag::ExtXKey {
+            method: key.method,
+            uri: key.uri,
+            iv: key.iv,
+            keyformat: key.keyformat,
+            keyformatversions: key.keyformatversions,
+            other: key.other,
+        }
+    }
+}
+
+impl From<&Map> for Tag {
+    fn from(map: &Map) -> Self {
+        Tag::ExtXMap {
+            uri: map.uri.clone(),
+            byterange: map.byterange,
+            other: map.other.clone(),
+        }
+    }
+}
+
+impl From<&Part> for Tag {
+    fn from(part: &Part) -> Self {
+        Tag::ExtXPart {
+            uri: part.uri.clone(),
+            duration: part.duration,
+            independent: part.independent,
+            other: part.other.clone(),
+        }
+    }
+}
@@ -21,536 +21,1085 @@
 //! ## Structs
 //!
 //! - `Playlist`: A struct representing an M3U8 playlist that contains a vector of `Tag` items.
+//! - `PlaylistKind`: Whether a playlist is a Master Playlist, a Media Playlist or ambiguous.
+//! - `media::MediaPlaylist` and `master::MasterPlaylist`: Typed views of a `Playlist`, convertible to and from it.
 //!
 //! ## Methods
 //!
-//! - `from_reader<R: BufRead>(reader: R) -> Result<Self, String>`: Creates a new `Playlist` by reading tags from a buffered reader.
-//! - `from_file<P: AsRef<Path>>(path: P) -> Result<Self, String>`: Creates a new `Playlist` by reading tags from a specified file.
+//! - `from_reader<R: BufRead>(reader: R) -> Result<Self, ParseError>`: Creates a new `Playlist` by reading tags from a buffered reader.
+//! - `from_file<P: AsRef<Path>>(path: P) -> Result<Self, ParseError>`: Creates a new `Playlist` by reading tags from a specified file.
 //! - `write_to_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()>`: Writes the playlist to a specified file.
+//! - `write_to<W: Write>(&self, writer: W) -> io::Result<()>`: Writes the playlist to a writer. `Playlist` also implements `Display`.
+//! - `write_with` and `to_string_with`: Write the playlist with `serialize::SerializeOptions`, e.g. for `CRLF` line endings.
+//! - `kind(&self) -> PlaylistKind`: Tells Master and Media Playlists apart by their tags.
+//! - `required_version(&self) -> u8`: Returns the lowest `EXT-X-VERSION` supporting the tags used.
 //! - `validate(&self) -> Result<(), Vec<ValidationError>>`: Validates the playlist according to RFC 8216, returning any validation errors.
+//! - `validate_with(&self, validator: &Validator) -> Result<(), Vec<ValidationError>>`: Validates the playlist with a configured `Validator`, e.g. one with custom rules.
+//!   Use `Validator::validate` directly for warnings.
 
 pub mod builder;
+pub mod master;
+pub mod media;
+pub mod serialize;
 
+use crate::m3u8::error::{LineError, ParseError, ParseErrorKind};
+use crate::m3u8::lexer::{Lexer, Line, Token};
+use crate::m3u8::parser::{parse_attributes, AttributeList, AttributeValue};
+use crate::m3u8::playlist::serialize::{write_playlist, SerializeOptions};
 use crate::m3u8::tags::Tag;
-use crate::m3u8::validation::ValidationError;
+use crate::m3u8::validation::{Profile, Severity, ValidationError, Validator};
+use crate::m3u8::values::{Decimal, KeyMethod, MediaType, PlaylistType, Resolution, TimeOffset};
+use std::fmt;
 use std::fs::File;
-use std::io::{self, BufRead, BufReader, Write};
+use std::io::{self, BufRead, BufReader, BufWriter};
 use std::path::Path;
-
-use regex::Regex;
+use std::str::FromStr;
 
 /// Represents a playlist containing multiple tags.
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Playlist {
     pub tags: Vec<Tag>,
 }
 
+/// The kind of a playlist, as returned by [`Playlist::kind`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum PlaylistKind {
+    /// The playlist contains Master Playlist tags only.
+    Master,
+    /// The playlist contains Media Playlist tags only.
+    Media,
+    /// The playlist contains both kinds of tags, or neither.
+    Ambiguous,
+}
+
 impl Playlist {
     /// Creates a new `Playlist` by reading tags from a buffered reader.
-    pub fn from_reader<R: BufRead>(mut reader: R) -> Result<Self, String> {
+    ///
+    /// The content is split into lines by the [`Lexer`]. `EXTINF` and `EXT-X-STREAM-INF`
+    /// tags take their URI from the next URI line; any tags in between are kept in order.
+    ///
+    /// Returns a [`ParseError`] locating the first malformed line.
+    pub fn from_reader<R: BufRead>(mut reader: R) -> Result<Self, ParseError> {
         let mut tags = Vec::new();
 
         let mut content = String::new();
-        reader
-            .read_to_string(&mut content)
-            .map_err(|e| e.to_string())?;
+        reader.read_to_string(&mut content)?;
 
-        for line in content.split("#") {
-            if line.is_empty() {
-                continue;
-            }
+        // The tag in `tags` that is still waiting for its URI line, with its source line.
+        let mut pending_uri: Option<(usize, Token)> = None;
 
-            if let Some(tag) = Self::parse_line(line)? {
-                tags.push(tag);
+        for token in Lexer::new(&content) {
+            match token.line {
+                Line::Tag(tag_line) => {
+                    let tag = Self::parse_line(tag_line)
+                        .map_err(|e| syntax_error(&token, e.offset, e.kind))?;
+                    if matches!(tag, Tag::ExtInf(..) | Tag::ExtXStreamInf { .. }) {
+                        if let Some((_, pending)) = pending_uri {
+                            return Err(syntax_error(&pending, 0, ParseErrorKind::MissingUri));
+                        }
+                        pending_uri = Some((tags.len(), token));
+                    }
+                    tags.push(tag);
+                }
+                Line::Comment(comment) => tags.push(Tag::Comment(comment.to_string())),
+                Line::Uri(uri) => match pending_uri.take().map(|(index, _)| &mut tags[index]) {
+                    Some(Tag::ExtInf(target, ..))
+                    | Some(Tag::ExtXStreamInf { uri: target, .. }) => {
+                        *target = uri.to_string();
+                    }
+                    _ => return Err(syntax_error(&token, 0, ParseErrorKind::UnexpectedUri)),
+                },
             }
         }
+
+        if let Some((_, pending)) = pending_uri {
+            return Err(syntax_error(&pending, 0, ParseErrorKind::MissingUri));
+        }
+
         Ok(Playlist { tags })
     }
 
     /// Creates a new `Playlist` by reading tags from a file.
-    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, String> {
-        let file = File::open(path).map_err(|e| e.to_string())?;
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, ParseError> {
+        let file = File::open(path)?;
         Self::from_reader(BufReader::new(file))
     }
 
     /// Writes the playlist to a file.
     pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
-        let mut file = File::create(path)?;
-        for tag in &self.tags {
-            writeln!(file, "{}", tag)?;
+        self.write_to(BufWriter::new(File::create(path)?))
+    }
+
+    /// Writes the playlist to a writer, one tag per line.
+    pub fn write_to<W: io::Write>(&self, writer: W) -> io::Result<()> {
+        self.write_with(writer, &SerializeOptions::default())
+    }
+
+    /// Writes the playlist to a writer with the given options.
+    pub fn write_with<W: io::Write>(
+        &self,
+        writer: W,
+        options: &SerializeOptions,
+    ) -> io::Result<()> {
+        write_playlist(self, writer, options)
+    }
+
+    /// Returns the playlist as a string written with the given options.
+    pub fn to_string_with(&self, options: &SerializeOptions) -> String {
+        let mut output = Vec::new();
+        self.write_with(&mut output, options)
+            .expect("writing to a Vec cannot fail");
+        String::from_utf8(output).expect("tags are valid UTF-8")
+    }
+
+    /// Returns whether this is a Master or a Media Playlist, judging by its tags.
+    ///
+    /// Tags allowed in both kinds, e.g. `EXT-X-VERSION` or `EXT-X-START`, are ignored.
+    pub fn kind(&self) -> PlaylistKind {
+        let master = self.tags.iter().any(Tag::is_master_tag);
+        let media = self.tags.iter().any(Tag::is_media_tag);
+        match (master, media) {
+            (true, false) => PlaylistKind::Master,
+            (false, true) => PlaylistKind::Media,
+            _ => PlaylistKind::Ambiguous,
         }
-        Ok(())
+    }
+
+    /// Returns the lowest `EXT-X-VERSION` that supports every feature this playlist uses,
+    /// following the protocol version compatibility table in RFC 8216 Section 7.
+    ///
+    /// `EXTINF` durations count as floating-point when they have a fractional part.
+    pub fn required_version(&self) -> u8 {
+        let iframes_only = self.tags.contains(&Tag::ExtXIFramesOnly);
+        self.tags
+            .iter()
+            .map(|tag| match tag {
+                Tag::ExtXMedia {
+                    instream_id: Some(instream_id),
+                    ..
+                } if instream_id.starts_with("SERVICE") => 7,
+                Tag::ExtXMap { .. } if !iframes_only => 6,
+                Tag::ExtXMap { .. } => 5,
+                Tag::ExtXKey {
+                    keyformat,
+                    keyformatversions,
+                    ..
+                } if keyformat.is_some() || keyformatversions.is_some() => 5,
+                Tag::ExtXByteRange(_) => 4,
+                Tag::ExtXIFramesOnly => 4,
+                Tag::ExtInf(_, duration, _) if duration.scale() > 0 => 3,
+                Tag::ExtXKey { iv: Some(_), .. } => 2,
+                _ => 1,
+            })
+            .max()
+            .unwrap_or(1)
     }
 
     /// Validates the playlist according to RFC 8216.
+    ///
+    /// This is a shortcut for the findings of [`Validator`] with [`Profile::Rfc8216`] that
+    /// have [`Severity::Error`].
     pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        self.validate_with(&Validator::new(Profile::Rfc8216))
+    }
+
+    /// Validates the playlist with the given validator, including its custom rules.
+    ///
+    /// Returns the findings that have [`Severity::Error`].
+    pub fn validate_with(&self, validator: &Validator) -> Result<(), Vec<ValidationError>> {
+        let errors: Vec<ValidationError> = validator
+            .validate(self)
+            .into_iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Error)
+            .map(|diagnostic| diagnostic.error)
+            .collect();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Runs every validation rule, returning all findings.
+    pub(crate) fn check(&self) -> Vec<ValidationError> {
         let mut errors = Vec::new();
 
         if !self.tags.iter().any(|tag| matches!(tag, Tag::ExtM3U)) {
             errors.push(ValidationError::MissingExtM3U);
         }
 
+        // A playlist is either a Master or a Media Playlist, never both (Section 4.1).
+        let master_tags = tag_names(self.tags.iter().filter(|tag| tag.is_master_tag()));
+        let media_tags = tag_names(self.tags.iter().filter(|tag| tag.is_media_tag()));
+        if !master_tags.is_empty() && !media_tags.is_empty() {
+            errors.push(ValidationError::MixedPlaylist {
+                master_tags,
+                media_tags,
+            });
+        }
+
+        // A playlist without EXT-X-VERSION is a version 1 playlist (Section 4.3.1.2).
+        let declared = self
+            .tags
+            .iter()
+            .find_map(|tag| match tag {
+                Tag::ExtXVersion(version) => Some(*version),
+                _ => None,
+            })
+            .unwrap_or(1);
+        let required = self.required_version();
+        if declared < required {
+            errors.push(ValidationError::VersionTooLow { declared, required });
+        }
+
         for tag in &self.tags {
             self.validate_tag(tag, &mut errors);
         }
 
-        if errors.is_empty() {
-            Ok(())
-        } else {
-            Err(errors)
-        }
+        self.validate_structure(&mut errors);
+        self.validate_renditions(&mut errors);
+        self.validate_low_latency(&mut errors);
+
+        errors
     }
 
-    fn parse_line(line: &str) -> Result<Option<Tag>, String> {
-        let trimmed = line.trim();
+    /// Parses a tag line, given without its leading `#`.
+    ///
+    /// Tags this crate does not know are returned as `Tag::Unknown`. Error offsets are
+    /// relative to `line`.
+    fn parse_line(line: &str) -> Result<Tag, LineError> {
+        let (name, value) = line.split_once(':').unwrap_or((line, ""));
+        let tag = Self::parse_tag(name, value).map_err(|e| e.shifted(name.len() + 1))?;
+        Ok(tag.unwrap_or_else(|| Tag::Unknown {
+            name: name.to_string(),
+            value: line.split_once(':').map(|(_, value)| value.to_string()),
+        }))
+    }
 
-        if trimmed.starts_with("EXTM3U") {
+    /// Parses the value of the tag `name`, returning `Ok(None)` if the tag is not known.
+    /// Error offsets are relative to `value`.
+    fn parse_tag(name: &str, value: &str) -> Result<Option<Tag>, LineError> {
+        if name == "EXTM3U" {
             return Ok(Some(Tag::ExtM3U));
         }
 
-        if trimmed.starts_with("EXT-X-VERSION") {
+        if name == "EXT-X-VERSION" {
             // Example: #EXT-X-VERSION:7
-            let version_re = Regex::new(r#"EXT-X-VERSION:(\d+)"#).unwrap();
-            if let Some(caps) = version_re.captures(trimmed) {
-                let version = caps.get(1).unwrap().as_str();
-                return Ok(Some(Tag::ExtXVersion(version.parse().unwrap())));
-            }
+            return Ok(Some(Tag::ExtXVersion(parse_number(name, value)?)));
         }
 
-        if trimmed.starts_with("EXT-X-TARGETDURATION") {
+        if name == "EXT-X-TARGETDURATION" {
             // Example #EXT-X-TARGETDURATION:10
-            let target_duration_re = Regex::new(r#"EXT-X-TARGETDURATION:(\d+)"#).unwrap();
-            if let Some(caps) = target_duration_re.captures(trimmed) {
-                let target = caps.get(1).unwrap().as_str();
-                return Ok(Some(Tag::ExtXTargetDuration(target.parse().unwrap())));
-            }
+            return Ok(Some(Tag::ExtXTargetDuration(parse_number(name, value)?)));
         }
 
-        if trimmed.starts_with("EXT-X-PLAYLIST-TYPE") {
+        if name == "EXT-X-PLAYLIST-TYPE" {
             // Example: #EXT-X-PLAYLIST-TYPE:EVENT
-            let playlist_type_re = Regex::new(r#"EXT-X-PLAYLIST-TYPE:(\w+)"#).unwrap();
-            if let Some(caps) = playlist_type_re.captures(trimmed) {
-                let playlist_type = caps.get(1).unwrap().as_str();
-                return Ok(Some(Tag::ExtXPlaylistType(playlist_type.to_string())));
-            }
+            return Ok(Some(Tag::ExtXPlaylistType(enumerated(
+                non_empty(name, value)?,
+                PlaylistType::Other,
+            ))));
         }
 
-        if trimmed.starts_with("EXT-X-MEDIA-SEQUENCE") {
+        if name == "EXT-X-MEDIA-SEQUENCE" {
             // Example: #EXT-X-MEDIA-SEQUENCE:0
-            let media_sequence_re = Regex::new(r#"EXT-X-MEDIA-SEQUENCE:(\d+)"#).unwrap();
-            if let Some(caps) = media_sequence_re.captures(trimmed) {
-                let sequence = caps.get(1).unwrap().as_str();
-                return Ok(Some(Tag::ExtXMediaSequence(sequence.parse().unwrap())));
-            }
+            return Ok(Some(Tag::ExtXMediaSequence(parse_number(name, value)?)));
         }
 
-        if trimmed.starts_with("EXT-X-DISCONTINUITY-SEQUENCE") {
+        if name == "EXT-X-DISCONTINUITY-SEQUENCE" {
             // Example: #EXT-X-DISCONTINUITY-SEQUENCE:0
-            let discontinuity_seq_re = Regex::new(r#"EXT-X-DISCONTINUITY-SEQUENCE:(\d+)"#).unwrap();
-            if let Some(caps) = discontinuity_seq_re.captures(trimmed) {
-                let sequence = caps.get(1).unwrap().as_str();
-                return Ok(Some(Tag::ExtXDiscontinuitySequence(
-                    sequence.parse().unwrap(),
-                )));
-            }
+            return Ok(Some(Tag::ExtXDiscontinuitySequence(parse_number(
+                name, value,
+            )?)));
         }
 
-        if trimmed.starts_with("EXT-X-ENDLIST") {
+        if name == "EXT-X-ENDLIST" {
             return Ok(Some(Tag::ExtXEndList));
         }
 
-        if trimmed.starts_with("EXT-X-KEY") {
-            // Example: #EXT-X-KEY:METHOD=AES-128,URI="https://example.com/key",IV="0x1234567890ABCDEF",KEYFORMAT="identity",KEYFORMATVERSIONS="1"
-            let key_re = Regex::new(r#"EXT-X-KEY:METHOD=([A-Za-z0-9\-]+),URI="([^"]+)"(?:,IV="([^"]*)")?(?:,KEYFORMAT="([^"]+)")?(?:,KEYFORMATVERSIONS="([^"]+)")?"#).unwrap();
-
-            if let Some(caps) = key_re.captures(trimmed) {
-                let method = caps.get(1).map(|m| m.as_str()).unwrap_or_default();
-                let uri = caps.get(2).map(|m| m.as_str().to_string());
-                let iv = caps.get(3).map(|m| m.as_str().to_string());
-                let keyformat = caps.get(4).map(|m| m.as_str().to_string());
-                let keyformatversions = caps.get(5).map(|m| m.as_str().to_string());
-
-                return Ok(Some(Tag::ExtXKey {
-                    method: method.to_string(),
-                    uri,
-                    iv,
-                    keyformat,
-                    keyformatversions,
-                }));
-            }
+        if name == "EXT-X-KEY" {
+            // Example: #EXT-X-KEY:METHOD=AES-128,URI="https://example.com/key",IV=0x1234567890ABCDEF,KEYFORMAT="identity",KEYFORMATVERSIONS="1"
+            let attributes = parse_attributes(value)?;
+            return Ok(Some(Tag::ExtXKey {
+                method: enumerated(
+                    required(attributes.enumerated_string("METHOD")?, "METHOD")?,
+                    KeyMethod::Other,
+                ),
+                uri: attributes.quoted_string("URI")?.map(str::to_string),
+                iv: attributes.parse("IV", AttributeList::hexadecimal_sequence)?,
+                keyformat: attributes.quoted_string("KEYFORMAT")?.map(str::to_string),
+                keyformatversions: attributes
+                    .quoted_string("KEYFORMATVERSIONS")?
+                    .map(str::to_string),
+                other: other_attributes(
+                    &attributes,
+                    &["METHOD", "URI", "IV", "KEYFORMAT", "KEYFORMATVERSIONS"],
+                ),
+            }));
         }
 
-        if trimmed.starts_with("EXT-X-MAP") {
+        if name == "EXT-X-MAP" {
             // Example: #EXT-X-MAP:URI="init.mp4",BYTERANGE="800@0"
-            let map_re = Regex::new(r#"EXT-X-MAP:URI="([^"]+)"(?:,BYTERANGE="([^"]+)")?"#).unwrap();
-            if let Some(caps) = map_re.captures(trimmed) {
-                let uri = caps.get(1).unwrap().as_str();
-                let byterange = caps.get(2).map(|m| m.as_str().to_string());
-                if byterange.clone().is_none() || byterange.clone().unwrap() == "" {
-                    return Ok(Some(Tag::ExtXMap {
-                        uri: uri.to_string(),
-                        byterange: None,
-                    }));
-                }
-
-                return Ok(Some(Tag::ExtXMap {
-                    uri: uri.to_string(),
-                    byterange,
-                }));
-            }
+            let attributes = parse_attributes(value)?;
+            return Ok(Some(Tag::ExtXMap {
+                uri: required(attributes.quoted_string("URI")?, "URI")?.to_string(),
+                byterange: attributes.parse("BYTERANGE", |attributes, name| {
+                    Ok(attributes
+                        .quoted_string(name)?
+                        .filter(|byterange| !byterange.is_empty()))
+                })?,
+                other: other_attributes(&attributes, &["URI", "BYTERANGE"]),
+            }));
         }
 
-        if trimmed.starts_with("EXT-X-PROGRAM-DATE-TIME") {
+        if name == "EXT-X-PROGRAM-DATE-TIME" {
             // Example: #EXT-X-PROGRAM-DATE-TIME:2024-11-05T12:00:00Z
-            let datetime_re = Regex::new(r#"EXT-X-PROGRAM-DATE-TIME:([^\s]+)"#).unwrap();
-            if let Some(caps) = datetime_re.captures(trimmed) {
-                let datetime = caps.get(1).unwrap().as_str();
-                return Ok(Some(Tag::ExtXProgramDateTime(datetime.to_string())));
-            }
+            let date_time = value.parse().map_err(|_| {
+                invalid_value(format!("{} value `{}` is not a date-time", name, value))
+            })?;
+            return Ok(Some(Tag::ExtXProgramDateTime(date_time)));
+        }
+
+        if name == "EXT-X-DATERANGE" {
+            // Example: #EXT-X-DATERANGE:ID="ad-1",START-DATE="2024-11-05T12:00:00Z",PLANNED-DURATION=30.0,X-AD-ID="1234"
+            let attributes = parse_attributes(value)?;
+            let (client_attributes, other) = other_attributes(
+                &attributes,
+                &[
+                    "ID",
+                    "CLASS",
+                    "START-DATE",
+                    "END-DATE",
+                    "DURATION",
+                    "PLANNED-DURATION",
+                    "END-ON-NEXT",
+                    "CUE",
+                ],
+            )
+            .into_iter()
+            .partition(|(name, _)| name.starts_with("X-"));
+            return Ok(Some(Tag::ExtXDateRange {
+                id: required(attributes.quoted_string("ID")?, "ID")?.to_string(),
+                class: attributes.quoted_string("CLASS")?.map(str::to_string),
+                start_date: required(
+                    attributes.parse("START-DATE", AttributeList::quoted_string)?,
+                    "START-DATE",
+                )?,
+                end_date: attributes.parse("END-DATE", AttributeList::quoted_string)?,
+                duration: attributes.decimal_float("DURATION")?.map(|d| d as f32),
+                planned_duration: attributes
+                    .decimal_float("PLANNED-DURATION")?
+                    .map(|d| d as f32),
+                end_on_next: attributes.yes_no("END-ON-NEXT")?,
+                cue: attributes.quoted_string("CUE")?.map(str::to_string),
+                client_attributes,
+                other,
+            }));
         }
 
-        if trimmed.starts_with("EXT-X-DISCONTINUITY") {
+        if name == "EXT-X-DISCONTINUITY" {
             return Ok(Some(Tag::ExtXDiscontinuity));
         }
 
-        if trimmed.starts_with("EXT-X-PART") {
-            // Example: #EXT-X-PART:URI="part1.ts",DURATION=5.0
-            let part_re = Regex::new(r#"EXT-X-PART:URI="([^\"]+)",DURATION=([\d\.]+)"#).unwrap();
-            if let Some(caps) = part_re.captures(trimmed) {
-                let uri = caps.get(1).unwrap().as_str();
-                let duration = caps.get(2).unwrap().as_str().parse().unwrap();
-                return Ok(Some(Tag::ExtXPart {
-                    uri: uri.to_string(),
-                    duration: Some(duration),
-                    independent: None,
-                }));
-            }
+        if name == "EXT-X-PART" {
+            // Example: #EXT-X-PART:URI="part1.ts",DURATION=5.0,INDEPENDENT=YES
+            let attributes = parse_attributes(value)?;
+            return Ok(Some(Tag::ExtXPart {
+                uri: required(attributes.quoted_string("URI")?, "URI")?.to_string(),
+                duration: attributes.decimal_float("DURATION")?.map(|d| d as f32),
+                independent: attributes.yes_no("INDEPENDENT")?,
+                other: other_attributes(&attributes, &["URI", "DURATION", "INDEPENDENT"]),
+            }));
         }
 
-        if trimmed.starts_with("EXT-X-PART-INF") {
+        if name == "EXT-X-PART-INF" {
             // Example: #EXT-X-PART-INF:PART-TARGET=5.0
             // Note: PART-HOLD-BACK is now in EXT-X-SERVER-CONTROL, not here
-            let part_inf_re = Regex::new(
-                r#"EXT-X-PART-INF:PART-TARGET=([\d\.]+)"#,
-            )
-            .unwrap();
-            if let Some(caps) = part_inf_re.captures(trimmed) {
-                let part_target_duration = caps.get(1).unwrap().as_str().parse().unwrap();
-                return Ok(Some(Tag::ExtXPartInf {
-                    part_target_duration,
-                    part_number: None,
-                }));
-            }
-            // Also try PART-TARGET-DURATION for backwards compatibility
-            let part_inf_re_alt = Regex::new(
-                r#"EXT-X-PART-INF:PART-TARGET-DURATION=([\d\.]+)"#,
-            )
-            .unwrap();
-            if let Some(caps) = part_inf_re_alt.captures(trimmed) {
-                let part_target_duration = caps.get(1).unwrap().as_str().parse().unwrap();
-                return Ok(Some(Tag::ExtXPartInf {
-                    part_target_duration,
-                    part_number: None,
-                }));
-            }
+            let attributes = parse_attributes(value)?;
+            // Also accept PART-TARGET-DURATION for backwards compatibility
+            let part_target_duration = match attributes.decimal_float("PART-TARGET")? {
+                Some(target) => target,
+                None => required(
+                    attributes.decimal_float("PART-TARGET-DURATION")?,
+                    "PART-TARGET",
+                )?,
+            };
+            return Ok(Some(Tag::ExtXPartInf {
+                part_target_duration: part_target_duration as f32,
+                part_number: attributes.decimal_integer("PART-NUMBER")?,
+                other: other_attributes(
+                    &attributes,
+                    &["PART-TARGET", "PART-TARGET-DURATION", "PART-NUMBER"],
+                ),
+            }));
         }
 
-        if trimmed.starts_with("EXT-X-SERVER-CONTROL") {
+        if name == "EXT-X-SERVER-CONTROL" {
             // Example: #EXT-X-SERVER-CONTROL:CAN-BLOCK-RELOAD=YES,PART-HOLD-BACK=1.0,CAN-SKIP-UNTIL=24.0
-            // Parse attributes flexibly
-            let mut can_play = None;
-            let mut can_seek = None;
-            let mut can_pause = None;
-            let mut min_buffer_time = None;
-            let mut can_block_reload = None;
-            let mut part_hold_back = None;
-            let mut can_skip_until = None;
-
-            // Extract all key=value pairs
-            let attr_re = Regex::new(r#"([A-Z-]+)=([^,]+)"#).unwrap();
-            for cap in attr_re.captures_iter(trimmed) {
-                let key = cap.get(1).unwrap().as_str();
-                let value = cap.get(2).unwrap().as_str();
-                match key {
-                    "CAN-PLAY" => can_play = Some(value == "YES"),
-                    "CAN-SEEK" => can_seek = Some(value == "YES"),
-                    "CAN-PAUSE" => can_pause = Some(value == "YES"),
-                    "MIN-BUFFER-TIME" => {
-                        if let Ok(val) = value.parse::<f32>() {
-                            min_buffer_time = Some(val);
-                        }
-                    }
-                    "CAN-BLOCK-RELOAD" => can_block_reload = Some(value == "YES"),
-                    "PART-HOLD-BACK" => {
-                        if let Ok(val) = value.parse::<f32>() {
-                            part_hold_back = Some(val);
-                        }
-                    }
-                    "CAN-SKIP-UNTIL" => {
-                        if let Ok(val) = value.parse::<f32>() {
-                            can_skip_until = Some(val);
-                        }
-                    }
-                    _ => {}
-                }
-            }
-
-            // Only create tag if at least one attribute was found
-            if can_play.is_some()
-                || can_seek.is_some()
-                || can_pause.is_some()
-                || min_buffer_time.is_some()
-                || can_block_reload.is_some()
-                || part_hold_back.is_some()
-                || can_skip_until.is_some()
-            {
-                return Ok(Some(Tag::ExtXServerControl {
-                    can_play,
-                    can_seek,
-                    can_pause,
-                    min_buffer_time,
-                    can_block_reload,
-                    part_hold_back,
-                    can_skip_until,
-                }));
-            }
+            let attributes = parse_attributes(value)?;
+            return Ok(Some(Tag::ExtXServerControl {
+                can_play: attributes.yes_no("CAN-PLAY")?,
+                can_seek: attributes.yes_no("CAN-SEEK")?,
+                can_pause: attributes.yes_no("CAN-PAUSE")?,
+                min_buffer_time: attributes
+                    .decimal_float("MIN-BUFFER-TIME")?
+                    .map(|t| t as f32),
+                can_block_reload: attributes.yes_no("CAN-BLOCK-RELOAD")?,
+                hold_back: attributes.decimal_float("HOLD-BACK")?.map(|t| t as f32),
+                part_hold_back: attributes
+                    .decimal_float("PART-HOLD-BACK")?
+                    .map(|t| t as f32),
+                can_skip_until: attributes
+                    .decimal_float("CAN-SKIP-UNTIL")?
+                    .map(|t| t as f32),
+                can_skip_dateranges: attributes.yes_no("CAN-SKIP-DATERANGES")?,
+                other: other_attributes(
+                    &attributes,
+                    &[
+                        "CAN-PLAY",
+                        "CAN-SEEK",
+                        "CAN-PAUSE",
+                        "MIN-BUFFER-TIME",
+                        "CAN-BLOCK-RELOAD",
+                        "HOLD-BACK",
+                        "PART-HOLD-BACK",
+                        "CAN-SKIP-UNTIL",
+                        "CAN-SKIP-DATERANGES",
+                    ],
+                ),
+            }));
         }
 
-        if trimmed.starts_with("EXT-X-SKIP") {
+        if name == "EXT-X-SKIP" {
             // Example: #EXT-X-SKIP:SKIPPED-SEGMENTS=3
             // Optional: ,RECENTLY-REMOVED-DATERANGES="id1\tid2"
-            let mut skipped_segments = None;
-            let mut recently_removed_dateranges = None;
-
-            let attr_re = Regex::new(r#"([A-Z-]+)=("([^"]*)"|([^,]+))"#).unwrap();
-            for cap in attr_re.captures_iter(trimmed) {
-                let key = cap.get(1).unwrap().as_str();
-                // Use quoted value (group 3) if present, otherwise unquoted (group 4)
-                let value = cap.get(3).or(cap.get(4)).unwrap().as_str();
-                match key {
-                    "SKIPPED-SEGMENTS" => {
-                        skipped_segments = value.parse::<u32>().ok();
-                    }
-                    "RECENTLY-REMOVED-DATERANGES" => {
-                        recently_removed_dateranges = Some(value.to_string());
-                    }
-                    _ => {}
-                }
-            }
-
-            if let Some(skipped) = skipped_segments {
-                return Ok(Some(Tag::ExtXSkip {
-                    skipped_segments: skipped,
-                    recently_removed_dateranges,
-                }));
-            }
+            let attributes = parse_attributes(value)?;
+            return Ok(Some(Tag::ExtXSkip {
+                skipped_segments: required(
+                    decimal_u32(&attributes, "SKIPPED-SEGMENTS")?,
+                    "SKIPPED-SEGMENTS",
+                )?,
+                recently_removed_dateranges: attributes
+                    .quoted_string("RECENTLY-REMOVED-DATERANGES")?
+                    .map(str::to_string),
+                other: other_attributes(
+                    &attributes,
+                    &["SKIPPED-SEGMENTS", "RECENTLY-REMOVED-DATERANGES"],
+                ),
+            }));
         }
 
-        if trimmed.starts_with("EXT-X-START") {
+        if name == "EXT-X-START" {
             // Example: #EXT-X-START:TIME-OFFSET=0.0,PRECISE=YES
-            let start_re =
-                Regex::new(r#"EXT-X-START:TIME-OFFSET=([\d\.]+),PRECISE=(\w+)"#).unwrap();
-            if let Some(caps) = start_re.captures(trimmed) {
-                let time_offset = caps.get(1).unwrap().as_str().to_string();
-                let precise = caps.get(2).unwrap().as_str() == "YES";
-                return Ok(Some(Tag::ExtXStart {
-                    time_offset,
-                    precise: Some(precise),
-                }));
-            }
+            let attributes = parse_attributes(value)?;
+            return Ok(Some(Tag::ExtXStart {
+                time_offset: TimeOffset(required(
+                    attributes.signed_decimal_float("TIME-OFFSET")?,
+                    "TIME-OFFSET",
+                )?),
+                precise: attributes.yes_no("PRECISE")?,
+                other: other_attributes(&attributes, &["TIME-OFFSET", "PRECISE"]),
+            }));
+        }
+
+        if name == "EXT-X-GAP" {
+            return Ok(Some(Tag::ExtXGap));
+        }
+
+        if name == "EXT-X-BITRATE" {
+            // Example: #EXT-X-BITRATE:1500
+            return Ok(Some(Tag::ExtXBitrate(parse_number(name, value)?)));
+        }
+
+        if name == "EXT-X-DEFINE" {
+            // Example: #EXT-X-DEFINE:NAME="host",VALUE="example.com"
+            parse_attributes(value)?;
+            return Ok(Some(Tag::ExtXDefine(value.to_string())));
         }
 
-        if trimmed.starts_with("EXT-X-INDEPENDENT-SEGMENTS") {
+        if name == "EXT-X-INDEPENDENT-SEGMENTS" {
             return Ok(Some(Tag::ExtXIndependentSegments));
         }
 
-        if trimmed.starts_with("EXT-X-STREAM-INF") {
+        if name == "EXT-X-I-FRAMES-ONLY" {
+            return Ok(Some(Tag::ExtXIFramesOnly));
+        }
+
+        if name == "EXT-X-STREAM-INF" {
             // Example: #EXT-X-STREAM-INF:BANDWIDTH=500000,RESOLUTION=640x360,CODECS="avc1.42c01e,mp4a.40.2"
-            let stream_inf_re = Regex::new(
-                r#"EXT-X-STREAM-INF:BANDWIDTH=(\d+),RESOLUTION=([^,]+),CODECS="([^"]+)"\s*(\S+)"#,
-            )
-            .unwrap();
-            if let Some(caps) = stream_inf_re.captures(trimmed) {
-                let bandwidth = caps.get(1).unwrap().as_str().parse().unwrap();
-                let resolution = caps.get(2).unwrap().as_str().to_string();
-                let codecs = caps.get(3).unwrap().as_str().to_string();
-                return Ok(Some(Tag::ExtXStreamInf {
-                    bandwidth,
-                    resolution: Some(resolution),
-                    codecs: Some(codecs),
-                    frame_rate: None,
-                    audio: None,
-                    video: None,
-                    subtitle: None,
-                    closed_captions: None,
-                }));
-            }
-        }
-
-        if trimmed.starts_with("EXT-X-MEDIA") {
+            // Attributes may appear in any order and all but BANDWIDTH are optional.
+            // The variant URI is on the following line and is filled in by `from_reader`.
+            let attributes = parse_attributes(value)?;
+            return Ok(Some(Tag::ExtXStreamInf {
+                bandwidth: required(decimal_u32(&attributes, "BANDWIDTH")?, "BANDWIDTH")?,
+                codecs: attributes.quoted_string("CODECS")?.map(str::to_string),
+                resolution: attributes
+                    .resolution("RESOLUTION")?
+                    .map(|(width, height)| Resolution { width, height }),
+                frame_rate: attributes.decimal_float("FRAME-RATE")?.map(|r| r as f32),
+                audio: attributes.quoted_string("AUDIO")?.map(str::to_string),
+                video: attributes.quoted_string("VIDEO")?.map(str::to_string),
+                subtitle: attributes.quoted_string("SUBTITLES")?.map(str::to_string),
+                closed_captions: closed_captions(&attributes)?,
+                average_bandwidth: decimal_u32(&attributes, "AVERAGE-BANDWIDTH")?,
+                hdcp_level: attributes
+                    .enumerated_string("HDCP-LEVEL")?
+                    .map(str::to_string),
+                video_range: attributes
+                    .enumerated_string("VIDEO-RANGE")?
+                    .map(str::to_string),
+                score: attributes.decimal_float("SCORE")?.map(|s| s as f32),
+                supplemental_codecs: attributes
+                    .quoted_string("SUPPLEMENTAL-CODECS")?
+                    .map(str::to_string),
+                allowed_cpc: attributes.quoted_string("ALLOWED-CPC")?.map(str::to_string),
+                stable_variant_id: attributes
+                    .quoted_string("STABLE-VARIANT-ID")?
+                    .map(str::to_string),
+                pathway_id: attributes.quoted_string("PATHWAY-ID")?.map(str::to_string),
+                req_video_layout: attributes
+                    .quoted_string("REQ-VIDEO-LAYOUT")?
+                    .map(str::to_string),
+                uri: String::new(),
+                other: other_attributes(
+                    &attributes,
+                    &[
+                        "BANDWIDTH",
+                        "CODECS",
+                        "RESOLUTION",
+                        "FRAME-RATE",
+                        "AUDIO",
+                        "VIDEO",
+                        "SUBTITLES",
+                        "CLOSED-CAPTIONS",
+                        "AVERAGE-BANDWIDTH",
+                        "HDCP-LEVEL",
+                        "VIDEO-RANGE",
+                        "SCORE",
+                        "SUPPLEMENTAL-CODECS",
+                        "ALLOWED-CPC",
+                        "STABLE-VARIANT-ID",
+                        "PATHWAY-ID",
+                        "REQ-VIDEO-LAYOUT",
+                    ],
+                ),
+            }));
+        }
+
+        if name == "EXT-X-MEDIA" {
             // Example: #EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID="audio",NAME="English",LANGUAGE="en",DEFAULT=YES,AUTOSELECT=YES,URI="audio_en.m3u8"
-            let media_re = Regex::new(r#"EXT-X-MEDIA:TYPE=(\w+),GROUP-ID="([^"]+)",(?:NAME="([^"]+)")?,(?:LANGUAGE="([^"]+)")?,(?:DEFAULT=(YES|NO))?,(?:AUTOSELECT=(YES|NO))?,(?:URI="([^"]+)")?,(?:CHARACTERISTICS=([^,]+))?,(?:LANGUAGE-CODEC="([^"]+)")?,(?:INSTREAM-ID="([^"]+)")?,(?:FORCED=(YES|NO))?"#).unwrap();
-            if let Some(caps) = media_re.captures(trimmed) {
-                let type_ = caps.get(1).unwrap().as_str().to_string();
-                let group_id = caps.get(2).unwrap().as_str().to_string();
-                let name = Some(caps.get(3).unwrap().as_str().to_string());
-                let language = Some(caps.get(4).unwrap().as_str().to_string());
-                let default = Some(caps.get(5).unwrap().as_str() == "YES");
-                let auto_select = Some(caps.get(6).unwrap().as_str() == "YES");
-                let uri = Some(caps.get(7).unwrap().as_str().to_string());
-                let instream_id = Some(caps.get(8).unwrap().as_str().to_string());
-                let language_codec = Some(caps.get(9).unwrap().as_str().to_string());
-                let characteristics = Some(caps.get(10).unwrap().as_str().to_string());
-                let forced = Some(caps.get(11).unwrap().as_str() == "YES");
-
-                return Ok(Some(Tag::ExtXMedia {
-                    type_,
-                    group_id,
-                    name,
-                    language,
-                    instream_id,
-                    language_codec,
-                    default,
-                    autoplay: auto_select,
-                    characteristics,
-                    uri,
-                    forced,
-                }));
-            }
-        }
-
-        if trimmed.starts_with("EXT-X-RENDITION-REPORT") {
-            // Example: #EXT-X-RENDITION-REPORT:URI="rendition_report.m3u8",BANDWIDTH=1000000
-            let rendition_report_re =
-                Regex::new(r#"EXT-X-RENDITION-REPORT:URI="([^"]+)",BANDWIDTH=(\d+)"#).unwrap();
-            if let Some(caps) = rendition_report_re.captures(trimmed) {
-                let uri = caps.get(1).unwrap().as_str().to_string();
-                let bandwidth = caps.get(2).unwrap().as_str().parse().unwrap();
-                return Ok(Some(Tag::ExtXRenditionReport { uri, bandwidth }));
-            }
-        }
-
-        if trimmed.starts_with("EXT-X-BYTERANGE") {
+            // Attributes may appear in any order; missing optional attributes are left as `None`.
+            let attributes = parse_attributes(value)?;
+            return Ok(Some(Tag::ExtXMedia {
+                type_: enumerated(
+                    required(attributes.enumerated_string("TYPE")?, "TYPE")?,
+                    MediaType::Other,
+                ),
+                group_id: required(attributes.quoted_string("GROUP-ID")?, "GROUP-ID")?.to_string(),
+                name: attributes.quoted_string("NAME")?.map(str::to_string),
+                uri: attributes.quoted_string("URI")?.map(str::to_string),
+                default: attributes.yes_no("DEFAULT")?,
+                autoselect: attributes.yes_no("AUTOSELECT")?,
+                characteristics: attributes
+                    .quoted_string("CHARACTERISTICS")?
+                    .map(str::to_string),
+                language: attributes.quoted_string("LANGUAGE")?.map(str::to_string),
+                instream_id: attributes.quoted_string("INSTREAM-ID")?.map(str::to_string),
+                language_codec: attributes
+                    .quoted_string("LANGUAGE-CODEC")?
+                    .map(str::to_string),
+                forced: attributes.yes_no("FORCED")?,
+                assoc_language: attributes
+                    .quoted_string("ASSOC-LANGUAGE")?
+                    .map(str::to_string),
+                channels: attributes.quoted_string("CHANNELS")?.map(str::to_string),
+                stable_rendition_id: attributes
+                    .quoted_string("STABLE-RENDITION-ID")?
+                    .map(str::to_string),
+                bit_depth: decimal_u32(&attributes, "BIT-DEPTH")?,
+                sample_rate: decimal_u32(&attributes, "SAMPLE-RATE")?,
+                other: other_attributes(
+                    &attributes,
+                    &[
+                        "TYPE",
+                        "GROUP-ID",
+                        "NAME",
+                        "URI",
+                        "DEFAULT",
+                        "AUTOSELECT",
+                        "CHARACTERISTICS",
+                        "LANGUAGE",
+                        "INSTREAM-ID",
+                        "LANGUAGE-CODEC",
+                        "FORCED",
+                        "ASSOC-LANGUAGE",
+                        "CHANNELS",
+                        "STABLE-RENDITION-ID",
+                        "BIT-DEPTH",
+                        "SAMPLE-RATE",
+                    ],
+                ),
+            }));
+        }
+
+        if name == "EXT-X-RENDITION-REPORT" {
+            // Example: #EXT-X-RENDITION-REPORT:URI="../1M/live.m3u8",LAST-MSN=273,LAST-PART=2
+            let attributes = parse_attributes(value)?;
+            return Ok(Some(Tag::ExtXRenditionReport {
+                uri: required(attributes.quoted_string("URI")?, "URI")?.to_string(),
+                last_msn: attributes.decimal_integer("LAST-MSN")?,
+                last_part: attributes.decimal_integer("LAST-PART")?,
+                bandwidth: decimal_u32(&attributes, "BANDWIDTH")?,
+                other: other_attributes(
+                    &attributes,
+                    &["URI", "LAST-MSN", "LAST-PART", "BANDWIDTH"],
+                ),
+            }));
+        }
+
+        if name == "EXT-X-BYTERANGE" {
             // Example: #EXT-X-BYTERANGE:500@1000
-            let byte_range_re = Regex::new(r#"EXT-X-BYTERANGE:([^\s]+)"#).unwrap();
-            if let Some(caps) = byte_range_re.captures(trimmed) {
-                let byte_range = caps.get(1).unwrap().as_str().to_string();
-                return Ok(Some(Tag::ExtXByteRange(byte_range)));
-            }
+            let byte_range = value.parse().map_err(|_| {
+                invalid_value(format!(
+                    "{} value `{}` is not of the form <n>[@<o>]",
+                    name, value
+                ))
+            })?;
+            return Ok(Some(Tag::ExtXByteRange(byte_range)));
         }
 
-        if trimmed.starts_with("EXT-X-I-FRAME-STREAM-INF") {
+        if name == "EXT-X-I-FRAME-STREAM-INF" {
             // Example: #EXT-X-I-FRAME-STREAM-INF:BANDWIDTH=300000,URI="iframe.m3u8"
-            let iframe_re =
-                Regex::new(r#"EXT-X-I-FRAME-STREAM-INF:BANDWIDTH=(\d+),URI="([^"]+)""#).unwrap();
-            if let Some(caps) = iframe_re.captures(trimmed) {
-                let bandwidth = caps.get(1).unwrap().as_str().parse().unwrap();
-                let uri = caps.get(2).unwrap().as_str().to_string();
-                return Ok(Some(Tag::ExtXIFrameStreamInf {
-                    bandwidth,
-                    codecs: None,
-                    resolution: None,
-                    frame_rate: None,
-                    uri,
-                }));
-            }
-        }
-
-        if trimmed.starts_with("EXT-X-SESSION-DATA") {
-            // Example: #EXT-X-SESSION-DATA:ID="session1",VALUE="value1",LANGUAGE="en"
-            let session_data_re =
-                Regex::new(r#"EXT-X-SESSION-DATA:ID="([^"]+)",VALUE="([^"]+)",LANGUAGE="([^"]+)""#)
-                    .unwrap();
-            if let Some(caps) = session_data_re.captures(trimmed) {
-                let id = caps.get(1).unwrap().as_str().to_string();
-                let value = caps.get(2).unwrap().as_str().to_string();
-                let language = Some(caps.get(3).unwrap().as_str().to_string());
-                return Ok(Some(Tag::ExtXSessionData {
-                    id,
-                    value,
-                    language,
-                }));
-            }
-        }
-
-        if trimmed.starts_with("EXT-X-PRELOAD-HINT") {
-            // Example: #EXT-X-PRELOAD-HINT:URI="preload_segment.ts",BYTERANGE="1000@2000"
-            let preload_hint_re =
-                Regex::new(r#"EXT-X-PRELOAD-HINT:URI="([^"]+)",BYTERANGE="([^"]+)""#).unwrap();
-            if let Some(caps) = preload_hint_re.captures(trimmed) {
-                let uri = caps.get(1).unwrap().as_str().to_string();
-                let byterange = Some(caps.get(2).unwrap().as_str().to_string());
-                return Ok(Some(Tag::ExtXPreloadHint { type_: None, uri, byterange }));
-            }
-        }
-
-        if trimmed.starts_with("EXTINF") {
-            // let split = trimmed.split("\n").collect::<Vec<_>>();
-            //
-            // let metadata_line = split.get(0).unwrap();
-            // let segment = split.get(1).unwrap();
-
-            let extinf_re = Regex::new(r#"EXTINF:(\d+(\.\d+)?),?\s*(.*?),?\s*(\S+)"#).unwrap();
-            if let Some(caps) = extinf_re.captures(trimmed) {
-                let duration: f32 = caps.get(1).unwrap().as_str().parse().unwrap();
-                let title = caps
-                    .get(3)
-                    .map(|m| m.as_str().trim().to_string())
-                    .unwrap_or_else(|| "".to_string());
-                let segment = caps.get(4).unwrap().as_str().trim().to_string();
-
-                if title.is_empty() {
-                    return Ok(Some(Tag::ExtInf(segment, duration, None)));
-                }
+            let attributes = parse_attributes(value)?;
+            return Ok(Some(Tag::ExtXIFrameStreamInf {
+                bandwidth: required(decimal_u32(&attributes, "BANDWIDTH")?, "BANDWIDTH")?,
+                codecs: attributes.quoted_string("CODECS")?.map(str::to_string),
+                resolution: attributes
+                    .resolution("RESOLUTION")?
+                    .map(|(width, height)| Resolution { width, height }),
+                frame_rate: attributes.decimal_float("FRAME-RATE")?.map(|r| r as f32),
+                uri: required(attributes.quoted_string("URI")?, "URI")?.to_string(),
+                other: other_attributes(
+                    &attributes,
+                    &["BANDWIDTH", "CODECS", "RESOLUTION", "FRAME-RATE", "URI"],
+                ),
+            }));
+        }
 
-                // Return parsed values wrapped in Tag::ExtInf
-                return Ok(Some(Tag::ExtInf(segment, duration, Some(title))));
+        if name == "EXT-X-SESSION-DATA" {
+            // Example: #EXT-X-SESSION-DATA:DATA-ID="com.example.title",VALUE="value1",LANGUAGE="en"
+            // or #EXT-X-SESSION-DATA:DATA-ID="com.example.lyrics",URI="lyrics.json"
+            let attributes = parse_attributes(value)?;
+            let data = attributes.quoted_string("VALUE")?.map(str::to_string);
+            let uri = attributes.quoted_string("URI")?.map(str::to_string);
+            if data.is_some() == uri.is_some() {
+                return Err(invalid_value(format!(
+                    "{} requires exactly one of VALUE and URI",
+                    name
+                )));
             }
+            return Ok(Some(Tag::ExtXSessionData {
+                id: required(attributes.quoted_string("DATA-ID")?, "DATA-ID")?.to_string(),
+                value: data,
+                uri,
+                language: attributes.quoted_string("LANGUAGE")?.map(str::to_string),
+                other: other_attributes(&attributes, &["DATA-ID", "VALUE", "URI", "LANGUAGE"]),
+            }));
+        }
+
+        if name == "EXT-X-PRELOAD-HINT" {
+            // Example: #EXT-X-PRELOAD-HINT:TYPE=PART,URI="preload_segment.ts",BYTERANGE="1000@2000"
+            let attributes = parse_attributes(value)?;
+            return Ok(Some(Tag::ExtXPreloadHint {
+                type_: attributes.enumerated_string("TYPE")?.map(str::to_string),
+                uri: required(attributes.quoted_string("URI")?, "URI")?.to_string(),
+                byterange: attributes.quoted_string("BYTERANGE")?.map(str::to_string),
+                other: other_attributes(&attributes, &["TYPE", "URI", "BYTERANGE"]),
+            }));
         }
 
-        if trimmed.starts_with("EXT-X-SESSION-KEY") {
-            // Example: #EXT-X-SESSION-KEY:METHOD=AES-128,URI="https://example.com/session_key",IV="0x9876543210ABCDEF"
-            let session_key_re =
-                Regex::new(r#"EXT-X-SESSION-KEY:METHOD=([^,]+),URI="([^"]+)",IV="([^"]+)""#)
-                    .unwrap();
-            if let Some(caps) = session_key_re.captures(trimmed) {
-                let method = caps.get(1).unwrap().as_str().to_string();
-                let uri = Some(caps.get(2).unwrap().as_str().to_string());
-                let iv = Some(caps.get(3).unwrap().as_str().to_string());
-                return Ok(Some(Tag::ExtXSessionKey { method, uri, iv }));
+        if name == "EXTINF" {
+            // Example: #EXTINF:5.005,Title
+            // The segment URI is on the following line and is filled in by `from_reader`.
+            let (duration, title) = value.split_once(',').unwrap_or((value, ""));
+            let is_digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+            let (integer, fraction) = duration.split_once('.').unwrap_or((duration, "0"));
+            if !is_digits(integer) || !is_digits(fraction) {
+                return Err(invalid_value(format!(
+                    "{} duration `{}` is not a decimal number",
+                    name, duration
+                )));
             }
+            let duration = duration.parse().map_err(|_| {
+                invalid_value(format!("{} duration `{}` is out of range", name, duration))
+            })?;
+            let title = Some(title.trim().to_string()).filter(|title| !title.is_empty());
+
+            return Ok(Some(Tag::ExtInf(String::new(), duration, title)));
+        }
+
+        if name == "EXT-X-SESSION-KEY" {
+            // Example: #EXT-X-SESSION-KEY:METHOD=AES-128,URI="https://example.com/session_key",IV=0x9876543210ABCDEF
+            let attributes = parse_attributes(value)?;
+            return Ok(Some(Tag::ExtXSessionKey {
+                method: enumerated(
+                    required(attributes.enumerated_string("METHOD")?, "METHOD")?,
+                    KeyMethod::Other,
+                ),
+                uri: attributes.quoted_string("URI")?.map(str::to_string),
+                iv: attributes.parse("IV", AttributeList::hexadecimal_sequence)?,
+                other: other_attributes(&attributes, &["METHOD", "URI", "IV"]),
+            }));
         }
 
         Ok(None)
     }
 
+    /// Checks the rules of RFC 8216 Section 4.3 that concern the playlist as a whole,
+    /// reporting the index of the offending tag in `self.tags`.
+    fn validate_structure(&self, errors: &mut Vec<ValidationError>) {
+        if let Some(index) = self.tags.iter().position(|tag| matches!(tag, Tag::ExtM3U)) {
+            if index != 0 {
+                errors.push(ValidationError::ExtM3UNotFirst { index });
+            }
+        }
+
+        // A zero target duration is already reported by `validate_tag`.
+        let target_duration = self.tags.iter().find_map(|tag| match tag {
+            Tag::ExtXTargetDuration(duration) if *duration > 0 => Some(*duration),
+            _ => None,
+        });
+        let first_segment = self
+            .tags
+            .iter()
+            .position(|tag| matches!(tag, Tag::ExtInf(..)));
+        let mut seen: Vec<&str> = Vec::new();
+        let mut has_end_list = false;
+        let mut vod = None;
+
+        for (index, tag) in self.tags.iter().enumerate() {
+            if is_rfc8216bis_tag(tag) {
+                errors.push(ValidationError::NotInRfc8216 {
+                    index,
+                    name: tag.name().to_string(),
+                });
+            }
+            if is_single_occurrence(tag) {
+                if seen.contains(&tag.name()) {
+                    errors.push(ValidationError::DuplicateTag {
+                        index,
+                        name: tag.name().to_string(),
+                    });
+                } else {
+                    seen.push(tag.name());
+                }
+            }
+
+            match tag {
+                // Durations are compared after rounding to the nearest integer (4.3.3.1).
+                Tag::ExtInf(_, duration, _) => {
+                    if let Some(target_duration) = target_duration {
+                        let rounded = duration.round(0).map(|rounded| rounded.value());
+                        if rounded.is_some_and(|rounded| rounded as i128 > target_duration as i128)
+                        {
+                            errors.push(ValidationError::SegmentDurationExceedsTarget {
+                                index,
+                                duration: duration.as_f64() as f32,
+                                target_duration,
+                            });
+                        }
+                    }
+                }
+                Tag::ExtXMediaSequence(_) if first_segment.is_some_and(|first| index > first) => {
+                    errors.push(ValidationError::MediaSequenceAfterSegment { index });
+                }
+                Tag::ExtXPlaylistType(playlist_type) => match playlist_type {
+                    PlaylistType::Vod => vod = vod.or(Some(index)),
+                    PlaylistType::Event => {}
+                    PlaylistType::Other(playlist_type) => {
+                        errors.push(ValidationError::InvalidPlaylistType {
+                            index,
+                            playlist_type: playlist_type.clone(),
+                        })
+                    }
+                },
+                Tag::ExtXEndList => has_end_list = true,
+                _ => {}
+            }
+        }
+
+        if let Some(index) = vod.filter(|_| !has_end_list) {
+            errors.push(ValidationError::MissingEndList { index });
+        }
+    }
+
+    /// Checks that the rendition groups referenced by `EXT-X-STREAM-INF` exist and that the
+    /// `EXT-X-MEDIA` tags of each group are consistent (RFC 8216 Section 4.3.4.1.1).
+    fn validate_renditions(&self, errors: &mut Vec<ValidationError>) {
+        // (TYPE, GROUP-ID, NAME, DEFAULT) of each rendition seen so far.
+        let mut renditions: Vec<(&MediaType, &str, Option<&str>, bool)> = Vec::new();
+
+        for (index, tag) in self.tags.iter().enumerate() {
+            let Tag::ExtXMedia {
+                type_,
+                group_id,
+                name,
+                uri,
+                default,
+                instream_id,
+                ..
+            } = tag
+            else {
+                continue;
+            };
+            let group = || {
+                renditions
+                    .iter()
+                    .filter(|(t, g, ..)| *t == type_ && g == group_id)
+            };
+
+            if *default == Some(true) && group().any(|(.., default)| *default) {
+                errors.push(ValidationError::MultipleDefaultRenditions {
+                    index,
+                    group_id: group_id.clone(),
+                });
+            }
+            if let Some(name) = name {
+                if group().any(|(_, _, other, _)| *other == Some(name.as_str())) {
+                    errors.push(ValidationError::DuplicateRenditionName {
+                        index,
+                        group_id: group_id.clone(),
+                        name: name.clone(),
+                    });
+                }
+            }
+            if let MediaType::Other(media_type) = type_ {
+                errors.push(ValidationError::InvalidMediaType {
+                    index,
+                    media_type: media_type.clone(),
+                });
+            }
+            if *type_ == MediaType::ClosedCaptions {
+                if instream_id.is_none() {
+                    errors.push(ValidationError::ClosedCaptionsMissingInstreamId { index });
+                }
+                if uri.is_some() {
+                    errors.push(ValidationError::ClosedCaptionsWithUri { index });
+                }
+            }
+
+            renditions.push((type_, group_id, name.as_deref(), *default == Some(true)));
+        }
+
+        for (index, tag) in self.tags.iter().enumerate() {
+            let Tag::ExtXStreamInf {
+                codecs,
+                audio,
+                video,
+                subtitle,
+                closed_captions,
+                ..
+            } = tag
+            else {
+                continue;
+            };
+            // Every EXT-X-STREAM-INF tag SHOULD include a CODECS attribute (4.3.4.2).
+            if codecs.is_none() {
+                errors.push(ValidationError::StreamInfMissingCodecs { index });
+            }
+            let references = [
+                (MediaType::Audio, audio),
+                (MediaType::Video, video),
+                (MediaType::Subtitles, subtitle),
+                (MediaType::ClosedCaptions, closed_captions),
+            ];
+            for (type_, group_id) in references {
+                let Some(group_id) = group_id.as_deref().filter(|id| *id != "NONE") else {
+                    continue;
+                };
+                if !renditions
+                    .iter()
+                    .any(|(t, g, ..)| **t == type_ && *g == group_id)
+                {
+                    errors.push(ValidationError::UnknownRenditionGroup {
+                        index,
+                        type_: type_.to_string(),
+                        group_id: group_id.to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Checks the Low-Latency HLS rules of RFC 8216bis Sections 4.4.3.7 to 4.4.5.3 for
+    /// `EXT-X-PART`, `EXT-X-PART-INF`, `EXT-X-SERVER-CONTROL`, `EXT-X-SKIP` and
+    /// `EXT-X-PRELOAD-HINT`.
+    fn validate_low_latency(&self, errors: &mut Vec<ValidationError>) {
+        let part_target = self.tags.iter().find_map(|tag| match tag {
+            Tag::ExtXPartInf {
+                part_target_duration,
+                ..
+            } => Some(*part_target_duration),
+            _ => None,
+        });
+        let target_duration = self.tags.iter().find_map(|tag| match tag {
+            Tag::ExtXTargetDuration(duration) => Some(*duration),
+            _ => None,
+        });
+        let can_skip_until = self.tags.iter().find_map(|tag| match tag {
+            Tag::ExtXServerControl { can_skip_until, .. } => *can_skip_until,
+            _ => None,
+        });
+
+        if part_target.is_none() {
+            if let Some(index) = self
+                .tags
+                .iter()
+                .position(|tag| matches!(tag, Tag::ExtXPart { .. }))
+            {
+                errors.push(ValidationError::MissingPartInf { index });
+            }
+        }
+
+        // The index of the first preload hint that is followed by a segment or a part.
+        let mut preload_hint = None;
+
+        for (index, tag) in self.tags.iter().enumerate() {
+            match tag {
+                Tag::ExtXPart {
+                    duration: Some(duration),
+                    ..
+                } => {
+                    if let Some(part_target) = part_target.filter(|target| duration > target) {
+                        errors.push(ValidationError::PartDurationExceedsTarget {
+                            index,
+                            duration: *duration,
+                            part_target,
+                        });
+                    }
+                }
+                Tag::ExtXServerControl {
+                    hold_back,
+                    part_hold_back,
+                    can_skip_until,
+                    ..
+                } => {
+                    if let (Some(hold_back), Some(target_duration)) = (hold_back, target_duration) {
+                        if (*hold_back as f64) < 3.0 * target_duration as f64 {
+                            errors.push(ValidationError::HoldBackTooShort {
+                                index,
+                                hold_back: *hold_back,
+                                target_duration,
+                            });
+                        }
+                    }
+                    if let (Some(part_hold_back), Some(part_target)) = (part_hold_back, part_target)
+                    {
+                        if *part_hold_back < 2.0 * part_target {
+                            errors.push(ValidationError::PartHoldBackTooShort {
+                                index,
+                                part_hold_back: *part_hold_back,
+                                part_target,
+                            });
+                        }
+                    }
+                    if let (Some(can_skip_until), Some(target_duration)) =
+                        (can_skip_until, target_duration)
+                    {
+                        if (*can_skip_until as f64) < 6.0 * target_duration as f64 {
+                            errors.push(ValidationError::CanSkipUntilTooShort {
+                                index,
+                                can_skip_until: *can_skip_until,
+                                target_duration,
+                            });
+                        }
+                    }
+                }
+                Tag::ExtXSkip { .. } if can_skip_until.is_none() => {
+                    errors.push(ValidationError::SkipWithoutCanSkipUntil { index });
+                }
+                _ => {}
+            }
+
+            match tag {
+                Tag::ExtXPreloadHint { .. } => {
+                    preload_hint = preload_hint.or(Some(index));
+                }
+                Tag::ExtInf(..) | Tag::ExtXPart { .. } => {
+                    if let Some(index) = preload_hint.take() {
+                        errors.push(ValidationError::PreloadHintNotAtTail { index });
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
     fn validate_tag(&self, tag: &Tag, errors: &mut Vec<ValidationError>) {
         match tag {
-            Tag::ExtXVersion(version) => {
-                if *version < 1 || *version > 7 {
-                    errors.push(ValidationError::InvalidVersion(*version));
-                }
+            Tag::ExtXVersion(version) if *version < 1 || *version > 7 => {
+                errors.push(ValidationError::InvalidVersion(*version));
             }
-            Tag::ExtInf(_, duration, _) if *duration <= 0.0 => {
-                errors.push(ValidationError::InvalidDuration(*duration));
+            Tag::ExtInf(_, duration, _) if *duration <= Decimal::ZERO => {
+                errors.push(ValidationError::InvalidDuration(duration.as_f64() as f32));
             }
             Tag::ExtXTargetDuration(duration) if *duration == 0 => {
                 errors.push(ValidationError::InvalidTargetDuration(*duration));
             }
-            Tag::ExtXKey { method, .. }
-                if !matches!(method.as_str(), "NONE" | "AES-128" | "SAMPLE-AES") =>
-            {
+            Tag::ExtXKey {
+                method: KeyMethod::Other(method),
+                ..
+            }
+            | Tag::ExtXSessionKey {
+                method: KeyMethod::Other(method),
+                ..
+            } => {
                 errors.push(ValidationError::InvalidKeyMethod(method.clone()));
             }
             Tag::ExtXMap { uri, .. } if uri.is_empty() => {
                 errors.push(ValidationError::InvalidMapUri);
             }
-            Tag::ExtXProgramDateTime(date_time) if date_time.is_empty() => {
-                errors.push(ValidationError::InvalidProgramDateTime);
+            Tag::ExtXSessionData { id, value, uri, .. } if value.is_some() == uri.is_some() => {
+                errors.push(ValidationError::InvalidSessionData(id.clone()));
+            }
+            Tag::ExtXDateRange {
+                id,
+                class,
+                start_date,
+                end_date,
+                duration,
+                planned_duration,
+                end_on_next,
+                ..
+            } => {
+                if id.is_empty() {
+                    errors.push(ValidationError::InvalidDateRangeId);
+                }
+                // END-DATE must not precede START-DATE, and may not be combined with
+                // END-ON-NEXT, which in turn requires a CLASS.
+                let end_date_valid = end_date
+                    .as_ref()
+                    .is_none_or(|end_date| end_date >= start_date);
+                let end_on_next_valid = *end_on_next != Some(true)
+                    || (class.is_some() && end_date.is_none() && duration.is_none());
+                if !end_date_valid || !end_on_next_valid {
+                    errors.push(ValidationError::InvalidDateRangeEndDate);
+                }
+                if let Some(duration) = duration.filter(|d| *d < 0.0) {
+                    errors.push(ValidationError::InvalidDuration(duration));
+                }
+                if let Some(planned_duration) = planned_duration.filter(|d| *d < 0.0) {
+                    errors.push(ValidationError::InvalidDateRangePlannedDuration(
+                        planned_duration,
+                    ));
+                }
             }
             Tag::ExtXGap => {
-                // Validation for EXT-X-GAP if necessary
-                // TODO: maybe we can make it configurable?
+                // Reported as `NotInRfc8216` by `validate_structure`.
             }
             Tag::ExtXBitrate(bitrate) if bitrate < &0 => {
                 errors.push(ValidationError::InvalidBitrate(*bitrate));
@@ -558,10 +1107,12 @@ impl Playlist {
             Tag::ExtXIndependentSegments => {
                 // No specific validation needed
             }
-            Tag::ExtXStart { time_offset, .. } if time_offset.is_empty() => {
+            Tag::ExtXStart { time_offset, .. } if !time_offset.0.is_finite() => {
                 errors.push(ValidationError::InvalidStartOffset);
             }
-            Tag::ExtXSkip { skipped_segments, .. } if *skipped_segments == 0 => {
+            Tag::ExtXSkip {
+                skipped_segments, ..
+            } if *skipped_segments == 0 => {
                 errors.push(ValidationError::InvalidSkipTag(
                     "SKIPPED-SEGMENTS must be positive".to_string(),
                 ));
@@ -572,11 +1123,168 @@ impl Playlist {
             Tag::ExtXRenditionReport { uri, .. } if uri.is_empty() => {
                 errors.push(ValidationError::InvalidRenditionReportUri);
             }
-            Tag::ExtXServerControl { .. } => {
-                // Add specific validations if needed
-                // TODO: maybe we can make it configurable?
+            Tag::ExtXServerControl {
+                can_skip_until: None,
+                can_skip_dateranges: Some(true),
+                ..
+            } => {
+                // CAN-SKIP-DATERANGES only applies when the server can skip segments.
+                errors.push(ValidationError::InvalidServerControl);
             }
             _ => {}
         }
+        if let Tag::ExtXKey { iv: Some(iv), .. } | Tag::ExtXSessionKey { iv: Some(iv), .. } = tag {
+            if iv.digits() != 32 {
+                errors.push(ValidationError::InvalidInitializationVector(iv.to_string()));
+            }
+        }
+    }
+}
+
+impl fmt::Display for Playlist {
+    /// Writes the playlist with the default `SerializeOptions`, one tag per line.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_string_with(&SerializeOptions::default()))
+    }
+}
+
+/// Returns `true` for tags that RFC 8216bis adds to RFC 8216.
+fn is_rfc8216bis_tag(tag: &Tag) -> bool {
+    matches!(
+        tag,
+        Tag::ExtXGap
+            | Tag::ExtXBitrate(_)
+            | Tag::ExtXDefine(_)
+            | Tag::ExtXServerControl { .. }
+            | Tag::ExtXPartInf { .. }
+            | Tag::ExtXPart { .. }
+            | Tag::ExtXPreloadHint { .. }
+            | Tag::ExtXRenditionReport { .. }
+            | Tag::ExtXSkip { .. }
+    )
+}
+
+/// Returns `true` for tags that must not appear more than once in a playlist.
+fn is_single_occurrence(tag: &Tag) -> bool {
+    matches!(
+        tag,
+        Tag::ExtM3U
+            | Tag::ExtXVersion(_)
+            | Tag::ExtXTargetDuration(_)
+            | Tag::ExtXMediaSequence(_)
+            | Tag::ExtXDiscontinuitySequence(_)
+            | Tag::ExtXEndList
+            | Tag::ExtXPlaylistType(_)
+            | Tag::ExtXIndependentSegments
+            | Tag::ExtXStart { .. }
+            | Tag::ExtXServerControl { .. }
+            | Tag::ExtXPartInf { .. }
+            | Tag::ExtXSkip { .. }
+    )
+}
+
+/// Returns the distinct names of `tags`, in order of first appearance.
+fn tag_names<'a>(tags: impl Iterator<Item = &'a Tag>) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for tag in tags {
+        if !names.iter().any(|name| name == tag.name()) {
+            names.push(tag.name().to_string());
+        }
+    }
+    names
+}
+
+/// Returns the value of a required attribute, or a `MissingAttribute` error.
+fn required<T>(value: Option<T>, attribute: &str) -> Result<T, LineError> {
+    value.ok_or_else(|| LineError::new(0, ParseErrorKind::MissingAttribute(attribute.to_string())))
+}
+
+/// Returns the attributes whose names are not in `known`, in source order.
+fn other_attributes(attributes: &AttributeList, known: &[&str]) -> Vec<(String, AttributeValue)> {
+    attributes
+        .iter()
+        .filter(|attribute| !known.contains(&attribute.name.as_str()))
+        .map(|attribute| (attribute.name.clone(), attribute.value.clone()))
+        .collect()
+}
+
+/// Reads the CLOSED-CAPTIONS attribute, which is either a quoted group ID or the
+/// enumerated string `NONE`.
+fn closed_captions(attributes: &AttributeList) -> Result<Option<String>, LineError> {
+    match attributes.enumerated_string("CLOSED-CAPTIONS") {
+        Ok(Some("NONE")) => Ok(Some("NONE".to_string())),
+        Ok(Some(other)) => Err(LineError::new(
+            attributes.get("CLOSED-CAPTIONS").unwrap().offset,
+            ParseErrorKind::InvalidAttributeValue {
+                name: "CLOSED-CAPTIONS".to_string(),
+                reason: format!("expected a quoted string or NONE, found `{}`", other),
+            },
+        )),
+        Ok(None) => Ok(None),
+        Err(_) => Ok(attributes
+            .quoted_string("CLOSED-CAPTIONS")?
+            .map(str::to_string)),
+    }
+}
+
+/// Reads a decimal-integer attribute that must fit in a `u32`.
+fn decimal_u32(attributes: &AttributeList, name: &str) -> Result<Option<u32>, LineError> {
+    match attributes.decimal_integer(name)? {
+        Some(value) => u32::try_from(value).map(Some).map_err(|_| {
+            LineError::new(
+                attributes.get(name).unwrap().offset,
+                ParseErrorKind::InvalidAttributeValue {
+                    name: name.to_string(),
+                    reason: format!("{} is out of range", value),
+                },
+            )
+        }),
+        None => Ok(None),
+    }
+}
+
+/// Parses the value of a tag holding a single decimal integer, e.g. `EXT-X-VERSION`.
+fn parse_number<T: std::str::FromStr>(tag: &str, value: &str) -> Result<T, LineError> {
+    if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid_value(format!(
+            "{} value `{}` is not a decimal integer",
+            tag, value
+        )));
+    }
+    value.parse().map_err(|_| {
+        invalid_value(format!(
+            "{} value `{}` is out of range for {}",
+            tag,
+            value,
+            std::any::type_name::<T>()
+        ))
+    })
+}
+
+/// Parses an enumerated value, keeping a value this crate does not know as `other`.
+fn enumerated<T: FromStr>(value: &str, other: fn(String) -> T) -> T {
+    value.parse().unwrap_or_else(|_| other(value.to_string()))
+}
+
+/// Returns the value of a tag that requires one, e.g. `EXT-X-PLAYLIST-TYPE`.
+fn non_empty<'a>(tag: &str, value: &'a str) -> Result<&'a str, LineError> {
+    if value.is_empty() {
+        return Err(invalid_value(format!("{} requires a value", tag)));
+    }
+    Ok(value)
+}
+
+/// Creates an `InvalidTagValue` error at the start of the tag value.
+fn invalid_value(reason: String) -> LineError {
+    LineError::new(0, ParseErrorKind::InvalidTagValue(reason))
+}
+
+/// Creates a `ParseError` for a problem `offset` bytes into the text of `token`.
+fn syntax_error(token: &Token, offset: usize, kind: ParseErrorKind) -> ParseError {
+    ParseError::Syntax {
+        line: token.number,
+        column: token.offset + offset + 1,
+        raw: token.raw.to_string(),
+        kind,
     }
 }
@@ -0,0 +1,186 @@
+//! Options for writing a [`Playlist`].
+//!
+//! `Playlist::write_to`, `Playlist::to_string` and `Playlist::write_to_file` write every
+//! tag as its `Display` implementation does, one per line. [`SerializeOptions`] changes
+//! the details, e.g. for players that expect `CRLF` line endings.
+//!
+//! # Example
+//!
+//! ```
+//! use m3u8_parser::m3u8::playlist::serialize::{LineEnding, SerializeOptions};
+//! use m3u8_parser::m3u8::playlist::Playlist;
+//! use m3u8_parser::m3u8::values::Precision;
+//!
+//! let data = "#EXTM3U\n#EXT-X-TARGETDURATION:10\n#EXTINF:9.009,\nfirst.ts\n";
+//! let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+//! assert_eq!(playlist.to_string(), data);
+//!
+//! let options = SerializeOptions {
+//!     line_ending: LineEnding::CrLf,
+//!     precision: Precision::Fixed(1),
+//!     ..SerializeOptions::default()
+//! };
+//! assert_eq!(
+//!     playlist.to_string_with(&options),
+//!     "#EXTM3U\r\n#EXT-X-TARGETDURATION:10\r\n#EXTINF:9.0,\r\nfirst.ts\r\n"
+//! );
+//! ```
+
+use crate::m3u8::parser::{parse_attributes, AttributeValue};
+use crate::m3u8::playlist::Playlist;
+use crate::m3u8::tags::Tag;
+use crate::m3u8::values::Precision;
+use std::io::{self, Write};
+
+/// The line terminator written after every line.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum LineEnding {
+    /// `\n`.
+    #[default]
+    Lf,
+    /// `\r\n`.
+    CrLf,
+}
+
+impl LineEnding {
+    fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        }
+    }
+}
+
+/// The order in which the attributes of an attribute list are written.
+///
+/// The order of the attributes in the source playlist is not kept, so neither order
+/// reproduces it.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum AttributeOrder {
+    /// The order in which the `Display` implementation of each tag writes them, e.g.
+    /// `METHOD` first for `EXT-X-KEY`. Attributes a tag does not model come last.
+    #[default]
+    Display,
+    /// Sorted by attribute name, so equal tags are always written the same way.
+    Canonical,
+}
+
+/// How a [`Playlist`] is written.
+///
+/// Only `EXTINF` durations are written exactly as they were read. Other decimal
+/// floating-point values, e.g. `FRAME-RATE`, `TIME-OFFSET` or the `DURATION` of
+/// `EXT-X-DATERANGE`, are stored as binary floating-point numbers and written in their
+/// shortest form: `FRAME-RATE=29.970` is written as `FRAME-RATE=29.97` and
+/// `TIME-OFFSET=0.0` as `TIME-OFFSET=0`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct SerializeOptions {
+    /// The line terminator. Defaults to `\n`.
+    pub line_ending: LineEnding,
+    /// The precision of `EXTINF` durations. Defaults to `Precision::Shortest`, which
+    /// writes durations as they are stored.
+    pub precision: Precision,
+    /// The order of attributes. Defaults to `AttributeOrder::Display`.
+    pub attribute_order: AttributeOrder,
+    /// Whether to write the comma of an `EXTINF` tag without a title, e.g.
+    /// `#EXTINF:10,`. RFC 8216 requires it; some legacy players expect `#EXTINF:10`.
+    /// Defaults to `true`.
+    pub empty_title_comma: bool,
+}
+
+impl Default for SerializeOptions {
+    fn default() -> Self {
+        Self {
+            line_ending: LineEnding::default(),
+            precision: Precision::default(),
+            attribute_order: AttributeOrder::default(),
+            empty_title_comma: true,
+        }
+    }
+}
+
+/// Writes `playlist` to `writer`, one tag per line.
+pub(crate) fn write_playlist<W: Write>(
+    playlist: &Playlist,
+    mut writer: W,
+    options: &SerializeOptions,
+) -> io::Result<()> {
+    let line_ending = options.line_ending.as_str();
+    for tag in &playlist.tags {
+        let text = match tag {
+            Tag::ExtInf(uri, duration, None) if !options.empty_title_comma => {
+                format!("#EXTINF:{}\n{}", options.precision.apply(*duration), uri)
+            }
+            Tag::ExtInf(uri, duration, title) => Tag::ExtInf(
+                uri.clone(),
+                options.precision.apply(*duration),
+                title.clone(),
+            )
+            .to_string(),
+            tag if options.attribute_order == AttributeOrder::Canonical
+                && has_attribute_list(tag) =>
+            {
+                sort_attributes(&tag.to_string())
+            }
+            tag => tag.to_string(),
+        };
+        for line in text.split('\n') {
+            write!(writer, "{}{}", line, line_ending)?;
+        }
+    }
+    writer.flush()
+}
+
+/// Returns `true` for tags whose value is an attribute list.
+fn has_attribute_list(tag: &Tag) -> bool {
+    matches!(
+        tag,
+        Tag::ExtXKey { .. }
+            | Tag::ExtXMap { .. }
+            | Tag::ExtXDateRange { .. }
+            | Tag::ExtXDefine(_)
+            | Tag::ExtXMedia { .. }
+            | Tag::ExtXStreamInf { .. }
+            | Tag::ExtXIFrameStreamInf { .. }
+            | Tag::ExtXSessionData { .. }
+            | Tag::ExtXSessionKey { .. }
+            | Tag::ExtXStart { .. }
+            | Tag::ExtXPartInf { .. }
+            | Tag::ExtXServerControl { .. }
+            | Tag::ExtXPart { .. }
+            | Tag::ExtXSkip { .. }
+            | Tag::ExtXPreloadHint { .. }
+            | Tag::ExtXRenditionReport { .. }
+    )
+}
+
+/// Sorts the attribute list on the first line of `text` by attribute name. The value of
+/// each attribute is kept as written. Text that does not parse is returned unchanged.
+fn sort_attributes(text: &str) -> String {
+    let (line, rest) = match text.split_once('\n') {
+        Some((line, rest)) => (line, Some(rest)),
+        None => (text, None),
+    };
+    let Some((name, value)) = line.split_once(':') else {
+        return text.to_string();
+    };
+    let Ok(attributes) = parse_attributes(value) else {
+        return text.to_string();
+    };
+
+    let mut attributes: Vec<_> = attributes.iter().collect();
+    attributes.sort_by(|a, b| a.name.cmp(&b.name));
+    let attributes: Vec<String> = attributes
+        .iter()
+        .map(|attribute| match attribute.value {
+            AttributeValue::QuotedString(_) => format!("{}=\"{}\"", attribute.name, attribute.text),
+            _ => format!("{}={}", attribute.name, attribute.text),
+        })
+        .collect();
+
+    let mut sorted = format!("{}:{}", name, attributes.join(","));
+    if let Some(rest) = rest {
+        sorted.push('\n');
+        sorted.push_str(rest);
+    }
+    sorted
+}
@@ -1,139 +1,539 @@
+use crate::m3u8::parser::AttributeValue;
+use crate::m3u8::values::{
+    ByteRange, DateTime, Decimal, InitializationVector, KeyMethod, MediaType, PlaylistType,
+    Resolution, TimeOffset,
+};
+
 /// Represents different types of tags found in an M3U8 playlist.
 ///
 /// Each variant corresponds to a specific type of tag defined in the M3U8 specification.
 /// This enum allows for easy manipulation and representation of these tags in a playlist.
+///
+/// # JSON
+///
+/// With the `serde` feature, a tag is an object with a single key, its HLS name. Tags
+/// without a value are just the name:
+///
+/// ```json
+/// "EXTM3U"
+/// {"EXT-X-VERSION": 7}
+/// {"EXTINF": {"uri": "first.ts", "duration": "9.009", "title": null}}
+/// {"EXT-X-KEY": {"method": "AES-128", "uri": "key.bin", "iv": null, "keyformat": null, "keyformatversions": null}}
+/// {"unknown": {"name": "EXT-X-CUE-OUT", "value": "30"}}
+/// {"comment": " a comment"}
+/// ```
+///
+/// Attributes are named after the HLS attribute in lower case, e.g. `group-id` or
+/// `data-id`; absent attributes are `null` and may be left out. Typed values are strings
+/// in their playlist form (see [`crate::m3u8::values`]). The `client-attributes` of
+/// `EXT-X-DATERANGE` are `[name, value]` pairs in source order, with values such as
+/// `{"quoted-string": "1234"}`. Attributes a tag does not model are kept the same way in
+/// `other`, which is left out when empty.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Tag {
     /// Indicates the start of an M3U8 file.
+    #[cfg_attr(feature = "serde", serde(rename = "EXTM3U"))]
     ExtM3U,
     /// Specifies the version of the M3U8 playlist.
+    #[cfg_attr(feature = "serde", serde(rename = "EXT-X-VERSION"))]
     ExtXVersion(u8),
     /// The EXT-X-PLAYLIST-TYPE tag provides mutability information about the
     //    Media Playlist file.  It applies to the entire Media Playlist file.
     //    It is OPTIONAL.  Its format is:
-    ExtXPlaylistType(String),
+    #[cfg_attr(feature = "serde", serde(rename = "EXT-X-PLAYLIST-TYPE"))]
+    ExtXPlaylistType(PlaylistType),
     /// Represents a media segment with a duration and an optional title.
-    ExtInf(String, f32, Option<String>),
+    #[cfg_attr(feature = "serde", serde(rename = "EXTINF", with = "extinf"))]
+    ExtInf(String, Decimal, Option<String>),
     /// Indicates the target duration for media segments.
+    #[cfg_attr(feature = "serde", serde(rename = "EXT-X-TARGETDURATION"))]
     ExtXTargetDuration(u64),
     /// Specifies the media sequence number.
+    #[cfg_attr(feature = "serde", serde(rename = "EXT-X-MEDIA-SEQUENCE"))]
     ExtXMediaSequence(u64),
     /// Represents a discontinuity sequence number.
+    #[cfg_attr(feature = "serde", serde(rename = "EXT-X-DISCONTINUITY-SEQUENCE"))]
     ExtXDiscontinuitySequence(u32),
     /// Marks the end of the playlist.
+    #[cfg_attr(feature = "serde", serde(rename = "EXT-X-ENDLIST"))]
     ExtXEndList,
     /// Contains information about encryption keys.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "EXT-X-KEY", rename_all = "kebab-case")
+    )]
     ExtXKey {
-        method: String,
+        method: KeyMethod,
         uri: Option<String>,
-        iv: Option<String>,
+        iv: Option<InitializationVector>,
         keyformat: Option<String>,
         keyformatversions: Option<String>,
+        /// Attributes this crate does not model, in source order.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Vec::is_empty")
+        )]
+        other: Vec<(String, AttributeValue)>,
     },
     /// Represents a mapping to an initialization segment.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "EXT-X-MAP", rename_all = "kebab-case")
+    )]
     ExtXMap {
         uri: String,
-        byterange: Option<String>,
+        byterange: Option<ByteRange>,
+        /// Attributes this crate does not model, in source order.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Vec::is_empty")
+        )]
+        other: Vec<(String, AttributeValue)>,
     },
     /// Specifies the program date and time.
-    ExtXProgramDateTime(String),
+    #[cfg_attr(feature = "serde", serde(rename = "EXT-X-PROGRAM-DATE-TIME"))]
+    ExtXProgramDateTime(DateTime),
+    /// Associates a date range with a set of attributes (RFC 8216 Section 4.3.2.7).
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "EXT-X-DATERANGE", rename_all = "kebab-case")
+    )]
+    ExtXDateRange {
+        id: String,
+        class: Option<String>,
+        start_date: DateTime,
+        end_date: Option<DateTime>,
+        duration: Option<f32>,
+        planned_duration: Option<f32>,
+        end_on_next: Option<bool>,
+        cue: Option<String>,
+        /// Client-defined `X-` attributes, in source order.
+        client_attributes: Vec<(String, AttributeValue)>,
+        /// Attributes this crate does not model, in source order.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Vec::is_empty")
+        )]
+        other: Vec<(String, AttributeValue)>,
+    },
     /// Represents a byte range.
-    ExtXByteRange(String),
+    #[cfg_attr(feature = "serde", serde(rename = "EXT-X-BYTERANGE"))]
+    ExtXByteRange(ByteRange),
     /// Defines a custom tag with a specific value.
+    #[cfg_attr(feature = "serde", serde(rename = "EXT-X-DEFINE"))]
     ExtXDefine(String),
     /// Represents media information.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "EXT-X-MEDIA", rename_all = "kebab-case")
+    )]
     ExtXMedia {
-        type_: String,
+        #[cfg_attr(feature = "serde", serde(rename = "type"))]
+        type_: MediaType,
         group_id: String,
         name: Option<String>,
         uri: Option<String>,
         default: Option<bool>,
-        autoplay: Option<bool>,
+        autoselect: Option<bool>,
         characteristics: Option<String>,
         language: Option<String>,
         instream_id: Option<String>,
         language_codec: Option<String>,
         forced: Option<bool>,
+        assoc_language: Option<String>,
+        channels: Option<String>,
+        stable_rendition_id: Option<String>,
+        bit_depth: Option<u32>,
+        sample_rate: Option<u32>,
+        /// Attributes this crate does not model, in source order.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Vec::is_empty")
+        )]
+        other: Vec<(String, AttributeValue)>,
     },
     /// Represents stream information.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "EXT-X-STREAM-INF", rename_all = "kebab-case")
+    )]
     ExtXStreamInf {
         bandwidth: u32,
         codecs: Option<String>,
-        resolution: Option<String>,
+        resolution: Option<Resolution>,
         frame_rate: Option<f32>,
         audio: Option<String>,
         video: Option<String>,
+        #[cfg_attr(feature = "serde", serde(rename = "subtitles"))]
         subtitle: Option<String>,
+        /// The closed-captions group ID, or `NONE` if the variant has no closed captions.
         closed_captions: Option<String>,
+        /// The average segment bit rate of the variant stream.
+        average_bandwidth: Option<u32>,
+        /// The required HDCP level: `TYPE-0`, `TYPE-1` or `NONE`.
+        hdcp_level: Option<String>,
+        /// The video range: `SDR`, `HLG` or `PQ`.
+        video_range: Option<String>,
+        /// The relative preference of this variant over other variants.
+        score: Option<f32>,
+        /// Codecs of a backwards-compatible enhancement layer, e.g. Dolby Vision profiles.
+        supplemental_codecs: Option<String>,
+        /// Content protection configurations allowed for each key format.
+        allowed_cpc: Option<String>,
+        /// A stable identifier for the variant across playlist reloads.
+        stable_variant_id: Option<String>,
+        /// The content steering pathway the variant belongs to.
+        pathway_id: Option<String>,
+        /// The required video layout, e.g. `CH-STEREO` for stereoscopic video.
+        req_video_layout: Option<String>,
+        /// The URI of the variant playlist, taken from the line following the tag.
+        uri: String,
+        /// Attributes this crate does not model, in source order.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Vec::is_empty")
+        )]
+        other: Vec<(String, AttributeValue)>,
     },
     /// Represents an I-frame stream information.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "EXT-X-I-FRAME-STREAM-INF", rename_all = "kebab-case")
+    )]
     ExtXIFrameStreamInf {
         bandwidth: u32,
         codecs: Option<String>,
-        resolution: Option<String>,
+        resolution: Option<Resolution>,
         frame_rate: Option<f32>,
         uri: String,
+        /// Attributes this crate does not model, in source order.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Vec::is_empty")
+        )]
+        other: Vec<(String, AttributeValue)>,
     },
     /// Indicates a gap in the playlist.
+    #[cfg_attr(feature = "serde", serde(rename = "EXT-X-GAP"))]
     ExtXGap,
     /// Specifies the bitrate of the stream.
+    #[cfg_attr(feature = "serde", serde(rename = "EXT-X-BITRATE"))]
     ExtXBitrate(u32),
     /// Indicates that segments are independent.
+    #[cfg_attr(feature = "serde", serde(rename = "EXT-X-INDEPENDENT-SEGMENTS"))]
     ExtXIndependentSegments,
+    /// Indicates that each media segment describes a single I-frame (RFC 8216 Section
+    /// 4.3.3.6).
+    #[cfg_attr(feature = "serde", serde(rename = "EXT-X-I-FRAMES-ONLY"))]
+    ExtXIFramesOnly,
     /// Specifies the start time offset.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "EXT-X-START", rename_all = "kebab-case")
+    )]
     ExtXStart {
-        time_offset: String,
+        time_offset: TimeOffset,
         precise: Option<bool>,
+        /// Attributes this crate does not model, in source order.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Vec::is_empty")
+        )]
+        other: Vec<(String, AttributeValue)>,
     },
     /// Provides server control information.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "EXT-X-SERVER-CONTROL", rename_all = "kebab-case")
+    )]
     ExtXServerControl {
         can_play: Option<bool>,
         can_seek: Option<bool>,
         can_pause: Option<bool>,
         min_buffer_time: Option<f32>,
         can_block_reload: Option<bool>,
+        hold_back: Option<f32>,
         part_hold_back: Option<f32>,
         can_skip_until: Option<f32>,
+        can_skip_dateranges: Option<bool>,
+        /// Attributes this crate does not model, in source order.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Vec::is_empty")
+        )]
+        other: Vec<(String, AttributeValue)>,
     },
     /// Represents part information.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "EXT-X-PART-INF", rename_all = "kebab-case")
+    )]
     ExtXPartInf {
+        #[cfg_attr(feature = "serde", serde(rename = "part-target"))]
         part_target_duration: f32,
         part_number: Option<u64>,
+        /// Attributes this crate does not model, in source order.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Vec::is_empty")
+        )]
+        other: Vec<(String, AttributeValue)>,
     },
     /// Represents a preload hint.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "EXT-X-PRELOAD-HINT", rename_all = "kebab-case")
+    )]
     ExtXPreloadHint {
+        #[cfg_attr(feature = "serde", serde(rename = "type"))]
         type_: Option<String>,
         uri: String,
         /// Optional byte range for the preload hint.
         byterange: Option<String>,
+        /// Attributes this crate does not model, in source order.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Vec::is_empty")
+        )]
+        other: Vec<(String, AttributeValue)>,
     },
     /// Represents a rendition report.
-    ExtXRenditionReport { uri: String, bandwidth: u32 },
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "EXT-X-RENDITION-REPORT", rename_all = "kebab-case")
+    )]
+    ExtXRenditionReport {
+        uri: String,
+        /// The media sequence number of the last segment of the rendition.
+        last_msn: Option<u64>,
+        /// The part index of the last partial segment of the rendition.
+        last_part: Option<u64>,
+        bandwidth: Option<u32>,
+        /// Attributes this crate does not model, in source order.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Vec::is_empty")
+        )]
+        other: Vec<(String, AttributeValue)>,
+    },
     /// Represents a part of a media segment.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "EXT-X-PART", rename_all = "kebab-case")
+    )]
     ExtXPart {
         uri: String,
         duration: Option<f32>,
         independent: Option<bool>,
+        /// Attributes this crate does not model, in source order.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Vec::is_empty")
+        )]
+        other: Vec<(String, AttributeValue)>,
     },
     /// Indicates skipped segments in a Playlist Delta Update (RFC 8216bis Section 4.4.5.2).
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "EXT-X-SKIP", rename_all = "kebab-case")
+    )]
     ExtXSkip {
         skipped_segments: u32,
         recently_removed_dateranges: Option<String>,
+        /// Attributes this crate does not model, in source order.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Vec::is_empty")
+        )]
+        other: Vec<(String, AttributeValue)>,
     },
     /// Indicates a discontinuity in the media stream.
+    #[cfg_attr(feature = "serde", serde(rename = "EXT-X-DISCONTINUITY"))]
     ExtXDiscontinuity,
     /// Represents session data for tracking and metadata.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "EXT-X-SESSION-DATA", rename_all = "kebab-case")
+    )]
+    ///
+    /// Exactly one of `value` and `uri` is present (RFC 8216 Section 4.3.4.4).
     ExtXSessionData {
+        #[cfg_attr(feature = "serde", serde(rename = "data-id"))]
         id: String,
-        value: String,
+        value: Option<String>,
+        uri: Option<String>,
         // Optional fields for additional parameters
         language: Option<String>,
+        /// Attributes this crate does not model, in source order.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Vec::is_empty")
+        )]
+        other: Vec<(String, AttributeValue)>,
     },
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "EXT-X-SESSION-KEY", rename_all = "kebab-case")
+    )]
     ExtXSessionKey {
-        method: String,
+        method: KeyMethod,
         uri: Option<String>,
-        iv: Option<String>,
+        iv: Option<InitializationVector>,
+        /// Attributes this crate does not model, in source order.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Vec::is_empty")
+        )]
+        other: Vec<(String, AttributeValue)>,
+    },
+    /// A tag this crate does not know, e.g. `#EXT-X-CUE-OUT:30`, kept verbatim.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "unknown", rename_all = "kebab-case")
+    )]
+    Unknown {
+        /// The tag name without the leading `#`, e.g. `EXT-X-CUE-OUT`.
+        name: String,
+        /// Everything after the first `:`, or `None` if the tag has no `:`.
+        value: Option<String>,
     },
+    /// A comment line. Holds the text after the leading `#`.
+    #[cfg_attr(feature = "serde", serde(rename = "comment"))]
+    Comment(String),
+}
+
+/// The JSON form of `EXTINF`: an object rather than the positional fields of
+/// `Tag::ExtInf`.
+#[cfg(feature = "serde")]
+mod extinf {
+    use crate::m3u8::values::Decimal;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize)]
+    struct ExtInfRef<'a> {
+        uri: &'a str,
+        duration: &'a Decimal,
+        title: &'a Option<String>,
+    }
+
+    #[derive(Deserialize)]
+    struct ExtInf {
+        uri: String,
+        duration: Decimal,
+        #[serde(default)]
+        title: Option<String>,
+    }
+
+    pub fn serialize<S: Serializer>(
+        uri: &str,
+        duration: &Decimal,
+        title: &Option<String>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        ExtInfRef {
+            uri,
+            duration,
+            title,
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<(String, Decimal, Option<String>), D::Error> {
+        let ExtInf {
+            uri,
+            duration,
+            title,
+        } = ExtInf::deserialize(deserializer)?;
+        Ok((uri, duration, title))
+    }
+}
+
+impl Tag {
+    /// Returns the tag name without the leading `#`, e.g. `EXT-X-VERSION`.
+    ///
+    /// Comments have no name and return an empty string.
+    pub fn name(&self) -> &str {
+        match self {
+            Tag::ExtM3U => "EXTM3U",
+            Tag::ExtXVersion(_) => "EXT-X-VERSION",
+            Tag::ExtXPlaylistType(_) => "EXT-X-PLAYLIST-TYPE",
+            Tag::ExtInf(..) => "EXTINF",
+            Tag::ExtXTargetDuration(_) => "EXT-X-TARGETDURATION",
+            Tag::ExtXMediaSequence(_) => "EXT-X-MEDIA-SEQUENCE",
+            Tag::ExtXDiscontinuitySequence(_) => "EXT-X-DISCONTINUITY-SEQUENCE",
+            Tag::ExtXEndList => "EXT-X-ENDLIST",
+            Tag::ExtXKey { .. } => "EXT-X-KEY",
+            Tag::ExtXMap { .. } => "EXT-X-MAP",
+            Tag::ExtXProgramDateTime(_) => "EXT-X-PROGRAM-DATE-TIME",
+            Tag::ExtXDateRange { .. } => "EXT-X-DATERANGE",
+            Tag::ExtXByteRange(_) => "EXT-X-BYTERANGE",
+            Tag::ExtXDefine(_) => "EXT-X-DEFINE",
+            Tag::ExtXMedia { .. } => "EXT-X-MEDIA",
+            Tag::ExtXStreamInf { .. } => "EXT-X-STREAM-INF",
+            Tag::ExtXIFrameStreamInf { .. } => "EXT-X-I-FRAME-STREAM-INF",
+            Tag::ExtXGap => "EXT-X-GAP",
+            Tag::ExtXBitrate(_) => "EXT-X-BITRATE",
+            Tag::ExtXIndependentSegments => "EXT-X-INDEPENDENT-SEGMENTS",
+            Tag::ExtXIFramesOnly => "EXT-X-I-FRAMES-ONLY",
+            Tag::ExtXStart { .. } => "EXT-X-START",
+            Tag::ExtXServerControl { .. } => "EXT-X-SERVER-CONTROL",
+            Tag::ExtXPartInf { .. } => "EXT-X-PART-INF",
+            Tag::ExtXPreloadHint { .. } => "EXT-X-PRELOAD-HINT",
+            Tag::ExtXRenditionReport { .. } => "EXT-X-RENDITION-REPORT",
+            Tag::ExtXPart { .. } => "EXT-X-PART",
+            Tag::ExtXSkip { .. } => "EXT-X-SKIP",
+            Tag::ExtXDiscontinuity => "EXT-X-DISCONTINUITY",
+            Tag::ExtXSessionData { .. } => "EXT-X-SESSION-DATA",
+            Tag::ExtXSessionKey { .. } => "EXT-X-SESSION-KEY",
+            Tag::Unknown { name, .. } => name,
+            Tag::Comment(_) => "",
+        }
+    }
+
+    /// Returns `true` for tags that may only appear in a Master Playlist
+    /// (RFC 8216 Section 4.3.4).
+    pub fn is_master_tag(&self) -> bool {
+        matches!(
+            self,
+            Tag::ExtXMedia { .. }
+                | Tag::ExtXStreamInf { .. }
+                | Tag::ExtXIFrameStreamInf { .. }
+                | Tag::ExtXSessionData { .. }
+                | Tag::ExtXSessionKey { .. }
+        )
+    }
+
+    /// Returns `true` for tags that may only appear in a Media Playlist, i.e. media
+    /// segment tags and media playlist tags (RFC 8216 Sections 4.3.2 and 4.3.3).
+    pub fn is_media_tag(&self) -> bool {
+        matches!(
+            self,
+            Tag::ExtInf(..)
+                | Tag::ExtXTargetDuration(_)
+                | Tag::ExtXMediaSequence(_)
+                | Tag::ExtXDiscontinuitySequence(_)
+                | Tag::ExtXEndList
+                | Tag::ExtXPlaylistType(_)
+                | Tag::ExtXIFramesOnly
+                | Tag::ExtXKey { .. }
+                | Tag::ExtXMap { .. }
+                | Tag::ExtXProgramDateTime(_)
+                | Tag::ExtXDateRange { .. }
+                | Tag::ExtXByteRange(_)
+                | Tag::ExtXDiscontinuity
+                | Tag::ExtXGap
+                | Tag::ExtXBitrate(_)
+                | Tag::ExtXServerControl { .. }
+                | Tag::ExtXPartInf { .. }
+                | Tag::ExtXPart { .. }
+                | Tag::ExtXPreloadHint { .. }
+                | Tag::ExtXRenditionReport { .. }
+                | Tag::ExtXSkip { .. }
+        )
+    }
 }
 
 impl std::fmt::Display for Tag {
@@ -164,11 +564,9 @@ impl std::fmt::Display for Tag {
             Tag::ExtXVersion(version) => write!(f, "#EXT-X-VERSION:{}", version),
             Tag::ExtInf(url, duration, title) => {
                 if let Some(title) = title {
-                    // Format with 3 decimal places
-                    write!(f, "#EXTINF:{:.4},{}\n {}", duration, title, url)
+                    write!(f, "#EXTINF:{},{}\n{}", duration, title, url)
                 } else {
-                    // Format with 3 decimal places
-                    write!(f, "#EXTINF:{:.4},\n{}", duration, url)
+                    write!(f, "#EXTINF:{},\n{}", duration, url)
                 }
             }
             Tag::ExtXTargetDuration(duration) => {
@@ -187,6 +585,7 @@ impl std::fmt::Display for Tag {
                 iv,
                 keyformat,
                 keyformatversions,
+                other,
             } => {
                 write!(f, "#EXT-X-KEY:METHOD={}", method)?;
                 if let Some(uri) = uri {
@@ -196,23 +595,69 @@ impl std::fmt::Display for Tag {
                     write!(f, ",IV={}", iv)?;
                 }
                 if let Some(keyformat) = keyformat {
-                    write!(f, ",KEYFORMAT={}", keyformat)?;
+                    write!(f, ",KEYFORMAT=\"{}\"", keyformat)?;
                 }
                 if let Some(keyformatversions) = keyformatversions {
-                    write!(f, ",KEYFORMATVERSIONS={}", keyformatversions)?;
+                    write!(f, ",KEYFORMATVERSIONS=\"{}\"", keyformatversions)?;
                 }
-                Ok(())
+                write_other(f, other)
             }
-            Tag::ExtXMap { uri, byterange } => {
+            Tag::ExtXMap {
+                uri,
+                byterange,
+                other,
+            } => {
                 write!(f, "#EXT-X-MAP:URI=\"{}\"", uri)?;
                 if let Some(byterange) = byterange {
-                    write!(f, ",BYTERANGE={}", byterange)?;
+                    write!(f, ",BYTERANGE=\"{}\"", byterange)?;
                 }
-                Ok(())
+                write_other(f, other)
             }
             Tag::ExtXProgramDateTime(date_time) => {
                 write!(f, "#EXT-X-PROGRAM-DATE-TIME:{}", date_time)
             }
+            Tag::ExtXDateRange {
+                id,
+                class,
+                start_date,
+                end_date,
+                duration,
+                planned_duration,
+                end_on_next,
+                cue,
+                client_attributes,
+                other,
+            } => {
+                write!(f, "#EXT-X-DATERANGE:ID=\"{}\"", id)?;
+                if let Some(class) = class {
+                    write!(f, ",CLASS=\"{}\"", class)?;
+                }
+                write!(f, ",START-DATE=\"{}\"", start_date)?;
+                if let Some(cue) = cue {
+                    write!(f, ",CUE=\"{}\"", cue)?;
+                }
+                if let Some(end_date) = end_date {
+                    write!(f, ",END-DATE=\"{}\"", end_date)?;
+                }
+                if let Some(duration) = duration {
+                    write!(f, ",DURATION={}", duration)?;
+                }
+                if let Some(planned_duration) = planned_duration {
+                    write!(f, ",PLANNED-DURATION={}", planned_duration)?;
+                }
+                for (name, value) in client_attributes {
+                    write!(f, ",{}={}", name, value)?;
+                }
+                write_other(f, other)?;
+                if let Some(end_on_next) = end_on_next {
+                    write!(
+                        f,
+                        ",END-ON-NEXT={}",
+                        if *end_on_next { "YES" } else { "NO" }
+                    )?;
+                }
+                Ok(())
+            }
             Tag::ExtXByteRange(byterange) => {
                 write!(f, "#EXT-X-BYTERANGE:{}", byterange)
             }
@@ -225,12 +670,18 @@ impl std::fmt::Display for Tag {
                 name,
                 uri,
                 default,
-                autoplay,
+                autoselect,
                 characteristics,
                 language,
                 instream_id,
                 language_codec,
                 forced,
+                assoc_language,
+                channels,
+                stable_rendition_id,
+                bit_depth,
+                sample_rate,
+                other,
             } => {
                 // Basic required fields
                 write!(f, "#EXT-X-MEDIA:TYPE={},GROUP-ID=\"{}\"", type_, group_id)?;
@@ -250,9 +701,9 @@ impl std::fmt::Display for Tag {
                     write!(f, ",DEFAULT={}", if *default { "YES" } else { "NO" })?;
                 }
 
-                // Optional autoplay field
-                if let Some(autoplay) = autoplay {
-                    write!(f, ",AUTOPLAY={}", if *autoplay { "YES" } else { "NO" })?;
+                // Optional autoselect field
+                if let Some(autoselect) = autoselect {
+                    write!(f, ",AUTOSELECT={}", if *autoselect { "YES" } else { "NO" })?;
                 }
 
                 // Optional forced field
@@ -267,7 +718,7 @@ impl std::fmt::Display for Tag {
 
                 // Optional characteristics field
                 if let Some(characteristics) = characteristics {
-                    write!(f, ",CHARACTERISTICS={}", characteristics)?;
+                    write!(f, ",CHARACTERISTICS=\"{}\"", characteristics)?;
                 }
 
                 // Optional language field
@@ -280,7 +731,32 @@ impl std::fmt::Display for Tag {
                     write!(f, ",LANGUAGE-CODEC=\"{}\"", language_codec)?;
                 }
 
-                Ok(())
+                // Optional assoc_language field
+                if let Some(assoc_language) = assoc_language {
+                    write!(f, ",ASSOC-LANGUAGE=\"{}\"", assoc_language)?;
+                }
+
+                // Optional channels field
+                if let Some(channels) = channels {
+                    write!(f, ",CHANNELS=\"{}\"", channels)?;
+                }
+
+                // Optional stable_rendition_id field
+                if let Some(stable_rendition_id) = stable_rendition_id {
+                    write!(f, ",STABLE-RENDITION-ID=\"{}\"", stable_rendition_id)?;
+                }
+
+                // Optional bit_depth field
+                if let Some(bit_depth) = bit_depth {
+                    write!(f, ",BIT-DEPTH={}", bit_depth)?;
+                }
+
+                // Optional sample_rate field
+                if let Some(sample_rate) = sample_rate {
+                    write!(f, ",SAMPLE-RATE={}", sample_rate)?;
+                }
+
+                write_other(f, other)
             }
             Tag::ExtXStreamInf {
                 bandwidth,
@@ -291,17 +767,52 @@ impl std::fmt::Display for Tag {
                 video,
                 subtitle,
                 closed_captions,
+                average_bandwidth,
+                hdcp_level,
+                video_range,
+                score,
+                supplemental_codecs,
+                allowed_cpc,
+                stable_variant_id,
+                pathway_id,
+                req_video_layout,
+                uri,
+                other,
             } => {
                 write!(f, "#EXT-X-STREAM-INF:BANDWIDTH={}", bandwidth)?;
+                if let Some(average_bandwidth) = average_bandwidth {
+                    write!(f, ",AVERAGE-BANDWIDTH={}", average_bandwidth)?;
+                }
                 if let Some(codecs) = codecs {
                     write!(f, ",CODECS=\"{}\"", codecs)?;
                 }
+                if let Some(supplemental_codecs) = supplemental_codecs {
+                    write!(f, ",SUPPLEMENTAL-CODECS=\"{}\"", supplemental_codecs)?;
+                }
                 if let Some(resolution) = resolution {
                     write!(f, ",RESOLUTION={}", resolution)?;
                 }
                 if let Some(frame_rate) = frame_rate {
                     write!(f, ",FRAME-RATE={}", frame_rate)?;
                 }
+                if let Some(hdcp_level) = hdcp_level {
+                    write!(f, ",HDCP-LEVEL={}", hdcp_level)?;
+                }
+                if let Some(video_range) = video_range {
+                    write!(f, ",VIDEO-RANGE={}", video_range)?;
+                }
+                if let Some(score) = score {
+                    write!(f, ",SCORE={}", score)?;
+                }
+                if let Some(allowed_cpc) = allowed_cpc {
+                    write!(f, ",ALLOWED-CPC=\"{}\"", allowed_cpc)?;
+                }
+                if let Some(stable_variant_id) = stable_variant_id {
+                    write!(f, ",STABLE-VARIANT-ID=\"{}\"", stable_variant_id)?;
+                }
+                if let Some(req_video_layout) = req_video_layout {
+                    write!(f, ",REQ-VIDEO-LAYOUT=\"{}\"", req_video_layout)?;
+                }
                 if let Some(audio) = audio {
                     write!(f, ",AUDIO=\"{}\"", audio)?;
                 }
@@ -311,10 +822,16 @@ impl std::fmt::Display for Tag {
                 if let Some(subtitle) = subtitle {
                     write!(f, ",SUBTITLES=\"{}\"", subtitle)?;
                 }
-                if let Some(closed_captions) = closed_captions {
-                    write!(f, ",CLOSED-CAPTIONS=\"{}\"", closed_captions)?;
+                match closed_captions.as_deref() {
+                    Some("NONE") => write!(f, ",CLOSED-CAPTIONS=NONE")?,
+                    Some(closed_captions) => write!(f, ",CLOSED-CAPTIONS=\"{}\"", closed_captions)?,
+                    None => {}
                 }
-                Ok(())
+                if let Some(pathway_id) = pathway_id {
+                    write!(f, ",PATHWAY-ID=\"{}\"", pathway_id)?;
+                }
+                write_other(f, other)?;
+                write!(f, "\n{}", uri)
             }
             Tag::ExtXIFrameStreamInf {
                 bandwidth,
@@ -322,6 +839,7 @@ impl std::fmt::Display for Tag {
                 resolution,
                 frame_rate,
                 uri,
+                other,
             } => {
                 write!(f, "#EXT-X-I-FRAME-STREAM-INF:BANDWIDTH={}", bandwidth)?;
                 if let Some(codecs) = codecs {
@@ -334,22 +852,24 @@ impl std::fmt::Display for Tag {
                     write!(f, ",FRAME-RATE={}", frame_rate)?;
                 }
                 write!(f, ",URI=\"{}\"", uri)?;
-                Ok(())
+                write_other(f, other)
             }
             Tag::ExtXGap => write!(f, "#EXT-X-GAP"),
             Tag::ExtXBitrate(bitrate) => {
                 write!(f, "#EXT-X-BITRATE:{}", bitrate)
             }
             Tag::ExtXIndependentSegments => write!(f, "#EXT-X-INDEPENDENT-SEGMENTS"),
+            Tag::ExtXIFramesOnly => write!(f, "#EXT-X-I-FRAMES-ONLY"),
             Tag::ExtXStart {
                 time_offset,
                 precise,
+                other,
             } => {
                 write!(f, "#EXT-X-START:TIME-OFFSET={}", time_offset)?;
                 if let Some(precise) = precise {
                     write!(f, ",PRECISE={}", if *precise { "YES" } else { "NO" })?;
                 }
-                Ok(())
+                write_other(f, other)
             }
             Tag::ExtXServerControl {
                 can_play,
@@ -357,116 +877,233 @@ impl std::fmt::Display for Tag {
                 can_pause,
                 min_buffer_time,
                 can_block_reload,
+                hold_back,
                 part_hold_back,
                 can_skip_until,
+                can_skip_dateranges,
+                other,
             } => {
                 write!(f, "#EXT-X-SERVER-CONTROL")?;
                 let mut first = true;
                 if let Some(can_block_reload) = can_block_reload {
-                    write!(f, "{}CAN-BLOCK-RELOAD={}", if first { ":" } else { "," }, if *can_block_reload { "YES" } else { "NO" })?;
+                    write!(
+                        f,
+                        "{}CAN-BLOCK-RELOAD={}",
+                        if first { ":" } else { "," },
+                        if *can_block_reload { "YES" } else { "NO" }
+                    )?;
+                    first = false;
+                }
+                if let Some(hold_back) = hold_back {
+                    write!(
+                        f,
+                        "{}HOLD-BACK={}",
+                        if first { ":" } else { "," },
+                        hold_back
+                    )?;
                     first = false;
                 }
                 if let Some(part_hold_back) = part_hold_back {
-                    write!(f, "{}PART-HOLD-BACK={}", if first { ":" } else { "," }, part_hold_back)?;
+                    write!(
+                        f,
+                        "{}PART-HOLD-BACK={}",
+                        if first { ":" } else { "," },
+                        part_hold_back
+                    )?;
                     first = false;
                 }
                 if let Some(can_skip_until) = can_skip_until {
-                    write!(f, "{}CAN-SKIP-UNTIL={}", if first { ":" } else { "," }, can_skip_until)?;
+                    write!(
+                        f,
+                        "{}CAN-SKIP-UNTIL={}",
+                        if first { ":" } else { "," },
+                        can_skip_until
+                    )?;
+                    first = false;
+                }
+                if let Some(can_skip_dateranges) = can_skip_dateranges {
+                    write!(
+                        f,
+                        "{}CAN-SKIP-DATERANGES={}",
+                        if first { ":" } else { "," },
+                        if *can_skip_dateranges { "YES" } else { "NO" }
+                    )?;
                     first = false;
                 }
                 if let Some(can_play) = can_play {
-                    write!(f, "{}CAN-PLAY={}", if first { ":" } else { "," }, if *can_play { "YES" } else { "NO" })?;
+                    write!(
+                        f,
+                        "{}CAN-PLAY={}",
+                        if first { ":" } else { "," },
+                        if *can_play { "YES" } else { "NO" }
+                    )?;
                     first = false;
                 }
                 if let Some(can_seek) = can_seek {
-                    write!(f, "{}CAN-SEEK={}", if first { ":" } else { "," }, if *can_seek { "YES" } else { "NO" })?;
+                    write!(
+                        f,
+                        "{}CAN-SEEK={}",
+                        if first { ":" } else { "," },
+                        if *can_seek { "YES" } else { "NO" }
+                    )?;
                     first = false;
                 }
                 if let Some(can_pause) = can_pause {
-                    write!(f, "{}CAN-PAUSE={}", if first { ":" } else { "," }, if *can_pause { "YES" } else { "NO" })?;
+                    write!(
+                        f,
+                        "{}CAN-PAUSE={}",
+                        if first { ":" } else { "," },
+                        if *can_pause { "YES" } else { "NO" }
+                    )?;
                     first = false;
                 }
                 if let Some(min_buffer_time) = min_buffer_time {
-                    write!(f, "{}MIN-BUFFER-TIME={}", if first { ":" } else { "," }, min_buffer_time)?;
+                    write!(
+                        f,
+                        "{}MIN-BUFFER-TIME={}",
+                        if first { ":" } else { "," },
+                        min_buffer_time
+                    )?;
+                    first = false;
+                }
+                for (name, value) in other {
+                    write!(f, "{}{}={}", if first { ":" } else { "," }, name, value)?;
+                    first = false;
                 }
                 Ok(())
             }
             Tag::ExtXPartInf {
                 part_target_duration,
                 part_number,
+                other,
             } => {
                 write!(f, "#EXT-X-PART-INF:PART-TARGET={}", part_target_duration)?;
                 if let Some(part_number) = part_number {
                     write!(f, ",PART-NUMBER={}", part_number)?;
                 }
-                Ok(())
+                write_other(f, other)
             }
-            Tag::ExtXPreloadHint { type_, uri, byterange } => {
+            Tag::ExtXPreloadHint {
+                type_,
+                uri,
+                byterange,
+                other,
+            } => {
                 let mut result = String::from("#EXT-X-PRELOAD-HINT:");
                 if let Some(type_val) = type_ {
                     result.push_str(&format!("TYPE={},", type_val));
                 }
                 result.push_str(&format!("URI=\"{}\"", uri));
                 if let Some(byterange) = byterange {
-                    result.push_str(&format!(",BYTERANGE={}", byterange));
+                    result.push_str(&format!(",BYTERANGE=\"{}\"", byterange));
                 }
-                write!(f, "{}", result)
+                write!(f, "{}", result)?;
+                write_other(f, other)
             }
-            Tag::ExtXRenditionReport { uri, bandwidth } => {
-                write!(
-                    f,
-                    "#EXT-X-RENDITION-REPORT:URI=\"{}\",BANDWIDTH={}",
-                    uri, bandwidth
-                )
+            Tag::ExtXRenditionReport {
+                uri,
+                last_msn,
+                last_part,
+                bandwidth,
+                other,
+            } => {
+                write!(f, "#EXT-X-RENDITION-REPORT:URI=\"{}\"", uri)?;
+                if let Some(last_msn) = last_msn {
+                    write!(f, ",LAST-MSN={}", last_msn)?;
+                }
+                if let Some(last_part) = last_part {
+                    write!(f, ",LAST-PART={}", last_part)?;
+                }
+                if let Some(bandwidth) = bandwidth {
+                    write!(f, ",BANDWIDTH={}", bandwidth)?;
+                }
+                write_other(f, other)
             }
-            Tag::ExtXPart { uri, duration, independent } => {
+            Tag::ExtXPart {
+                uri,
+                duration,
+                independent,
+                other,
+            } => {
                 write!(f, "#EXT-X-PART:URI=\"{}\"", uri)?;
                 if let Some(duration) = duration {
                     write!(f, ",DURATION={}", duration)?;
                 }
                 if let Some(independent) = independent {
-                    if *independent {
-                        write!(f, ",INDEPENDENT=YES")?;
-                    }
+                    write!(
+                        f,
+                        ",INDEPENDENT={}",
+                        if *independent { "YES" } else { "NO" }
+                    )?;
                 }
-                Ok(())
+                write_other(f, other)
             }
             Tag::ExtXSkip {
                 skipped_segments,
                 recently_removed_dateranges,
+                other,
             } => {
                 write!(f, "#EXT-X-SKIP:SKIPPED-SEGMENTS={}", skipped_segments)?;
                 if let Some(dateranges) = recently_removed_dateranges {
                     write!(f, ",RECENTLY-REMOVED-DATERANGES=\"{}\"", dateranges)?;
                 }
-                Ok(())
+                write_other(f, other)
             }
             Tag::ExtXDiscontinuity => write!(f, "#EXT-X-DISCONTINUITY"),
             Tag::ExtXSessionData {
                 id,
                 value,
+                uri,
                 language,
+                other,
             } => {
-                write!(f, "#EXT-X-SESSION-DATA:ID=\"{}\",VALUE=\"{}\"", id, value)?;
+                write!(f, "#EXT-X-SESSION-DATA:DATA-ID=\"{}\"", id)?;
+                if let Some(value) = value {
+                    write!(f, ",VALUE=\"{}\"", value)?;
+                }
+                if let Some(uri) = uri {
+                    write!(f, ",URI=\"{}\"", uri)?;
+                }
                 if let Some(language) = language {
                     write!(f, ",LANGUAGE=\"{}\"", language)?;
                 }
-                Ok(())
+                write_other(f, other)
             }
-            Tag::ExtXSessionKey { method, uri, iv } => {
+            Tag::ExtXSessionKey {
+                method,
+                uri,
+                iv,
+                other,
+            } => {
                 write!(f, "#EXT-X-SESSION-KEY:METHOD={}", method)?;
                 if let Some(uri) = uri {
-                    write!(f, ",URI={}", uri)?;
+                    write!(f, ",URI=\"{}\"", uri)?;
                 }
                 if let Some(iv) = iv {
                     write!(f, ",IV={}", iv)?;
                 }
-                Ok(())
+                write_other(f, other)
             }
             Tag::ExtXPlaylistType(playlist_type) => {
                 write!(f, "#EXT-X-PLAYLIST-TYPE:{}", playlist_type)?;
                 Ok(())
             }
+            Tag::Unknown { name, value } => match value {
+                Some(value) => write!(f, "#{}:{}", name, value),
+                None => write!(f, "#{}", name),
+            },
+            Tag::Comment(comment) => write!(f, "#{}", comment),
         }
     }
 }
+
+/// Writes the attributes a tag does not model, each preceded by a comma.
+fn write_other(
+    f: &mut std::fmt::Formatter<'_>,
+    other: &[(String, AttributeValue)],
+) -> std::fmt::Result {
+    for (name, value) in other {
+        write!(f, ",{}={}", name, value)?;
+    }
+    Ok(())
+}
@@ -1,10 +1,31 @@
 #[cfg(test)]
 mod tests {
-    use crate::m3u8::playlist::builder::PlaylistBuilder;
-    use crate::m3u8::playlist::Playlist;
+    use crate::m3u8::authoring::{Checker, Failure};
+    use crate::m3u8::error::{ParseError, ParseErrorKind};
+    use crate::m3u8::parser::{parse_attributes, AttributeValue};
+    use crate::m3u8::playlist::builder::{MediaPlaylistBuilder, PlaylistBuilder, SegmentBuilder};
+    use crate::m3u8::playlist::master::{MasterPlaylist, Variant};
+    use crate::m3u8::playlist::media::{Key, Map, MediaPlaylist, Part, Segment};
+    use crate::m3u8::playlist::serialize::{AttributeOrder, LineEnding, SerializeOptions};
+    use crate::m3u8::playlist::{Playlist, PlaylistKind};
     use crate::m3u8::tags::Tag;
-    use crate::m3u8::validation::ValidationError;
+    use crate::m3u8::validation::{
+        Diagnostic, Profile, RuleViolation, Severity, ValidationError, ValidationRule, Validator,
+    };
+    use crate::m3u8::values::{
+        ByteRange, DateTime, Decimal, InitializationVector, KeyMethod, MediaType, PlaylistType,
+        Precision, Resolution, TimeOffset,
+    };
     use std::io::Write;
+    use std::time::Duration;
+
+    fn decimal(value: &str) -> Decimal {
+        value.parse().unwrap()
+    }
+
+    fn date_time(value: &str) -> DateTime {
+        value.parse().unwrap()
+    }
 
     #[test]
     fn test_parse_simple_playlist() {
@@ -30,17 +51,17 @@ https://media.example.com/third.ts
                 Tag::ExtXTargetDuration(10),
                 Tag::ExtInf(
                     "https://media.example.com/first.ts".to_string(),
-                    5.005,
+                    decimal("5.005"),
                     None
                 ),
                 Tag::ExtInf(
                     "https://media.example.com/second.ts".to_string(),
-                    5.005,
+                    decimal("5.005"),
                     None
                 ),
                 Tag::ExtInf(
                     "https://media.example.com/third.ts".to_string(),
-                    3.003,
+                    decimal("3.003"),
                     None
                 ),
                 Tag::ExtXEndList,
@@ -57,17 +78,17 @@ https://media.example.com/third.ts
                 Tag::ExtXTargetDuration(10),
                 Tag::ExtInf(
                     "https://media.example.com/first.ts".to_string(),
-                    5.005,
+                    decimal("5.005"),
                     None,
                 ),
                 Tag::ExtInf(
                     "https://media.example.com/second.ts".to_string(),
-                    5.005,
+                    decimal("5.005"),
                     None,
                 ),
                 Tag::ExtInf(
                     "https://media.example.com/third.ts".to_string(),
-                    3.003,
+                    decimal("3.003"),
                     None,
                 ),
                 Tag::ExtXEndList,
@@ -83,11 +104,11 @@ https://media.example.com/third.ts
         let expected = r#"#EXTM3U
 #EXT-X-VERSION:7
 #EXT-X-TARGETDURATION:10
-#EXTINF:5.0050,
+#EXTINF:5.005,
 https://media.example.com/first.ts
-#EXTINF:5.0050,
+#EXTINF:5.005,
 https://media.example.com/second.ts
-#EXTINF:3.0030,
+#EXTINF:3.003,
 https://media.example.com/third.ts
 #EXT-X-ENDLIST
 "#;
@@ -119,25 +140,26 @@ https://media.example.com/third.ts
                 Tag::ExtXVersion(7),
                 Tag::ExtXTargetDuration(10),
                 Tag::ExtXKey {
-                    method: "AES-128".to_string(),
+                    method: KeyMethod::Aes128,
                     uri: Some("https://priv.example.com/key.php?r=52".to_string()),
                     iv: None,
                     keyformat: None,
                     keyformatversions: None,
+                    other: Vec::new(),
                 },
                 Tag::ExtInf(
                     "https://media.example.com/first.ts".to_string(),
-                    5.005,
+                    decimal("5.005"),
                     None
                 ),
                 Tag::ExtInf(
                     "https://media.example.com/second.ts".to_string(),
-                    5.005,
+                    decimal("5.005"),
                     None
                 ),
                 Tag::ExtInf(
                     "https://media.example.com/third.ts".to_string(),
-                    3.003,
+                    decimal("3.003"),
                     None
                 ),
                 Tag::ExtXEndList,
@@ -153,25 +175,26 @@ https://media.example.com/third.ts
                 Tag::ExtXVersion(7),
                 Tag::ExtXTargetDuration(10),
                 Tag::ExtXKey {
-                    method: "AES-128".to_string(),
+                    method: KeyMethod::Aes128,
                     uri: Some("https://priv.example.com/key.php?r=52".to_string()),
                     iv: None,
                     keyformat: None,
                     keyformatversions: None,
+                    other: Vec::new(),
                 },
                 Tag::ExtInf(
                     "https://media.example.com/first.ts".to_string(),
-                    5.005,
+                    decimal("5.005"),
                     None,
                 ),
                 Tag::ExtInf(
                     "https://media.example.com/second.ts".to_string(),
-                    5.005,
+                    decimal("5.005"),
                     None,
                 ),
                 Tag::ExtInf(
                     "https://media.example.com/third.ts".to_string(),
-                    3.003,
+                    decimal("3.003"),
                     None,
                 ),
                 Tag::ExtXEndList,
@@ -188,11 +211,11 @@ https://media.example.com/third.ts
 #EXT-X-VERSION:7
 #EXT-X-TARGETDURATION:10
 #EXT-X-KEY:METHOD=AES-128,URI="https://priv.example.com/key.php?r=52"
-#EXTINF:5.0050,
+#EXTINF:5.005,
 https://media.example.com/first.ts
-#EXTINF:5.0050,
+#EXTINF:5.005,
 https://media.example.com/second.ts
-#EXTINF:3.0030,
+#EXTINF:3.003,
 https://media.example.com/third.ts
 #EXT-X-ENDLIST
 "#;
@@ -226,20 +249,21 @@ https://media.example.com/third.ts
                 Tag::ExtXMap {
                     uri: "init.mp4".to_string(),
                     byterange: None,
+                    other: Vec::new(),
                 },
                 Tag::ExtInf(
                     "https://media.example.com/first.ts".to_string(),
-                    5.005,
+                    decimal("5.005"),
                     None
                 ),
                 Tag::ExtInf(
                     "https://media.example.com/second.ts".to_string(),
-                    5.005,
+                    decimal("5.005"),
                     None
                 ),
                 Tag::ExtInf(
                     "https://media.example.com/third.ts".to_string(),
-                    3.003,
+                    decimal("3.003"),
                     None
                 ),
                 Tag::ExtXEndList,
@@ -257,20 +281,21 @@ https://media.example.com/third.ts
                 Tag::ExtXMap {
                     uri: "init.mp4".to_string(),
                     byterange: None,
+                    other: Vec::new(),
                 },
                 Tag::ExtInf(
                     "https://media.example.com/first.ts".to_string(),
-                    5.005,
+                    decimal("5.005"),
                     None,
                 ),
                 Tag::ExtInf(
                     "https://media.example.com/second.ts".to_string(),
-                    5.005,
+                    decimal("5.005"),
                     None,
                 ),
                 Tag::ExtInf(
                     "https://media.example.com/third.ts".to_string(),
-                    3.003,
+                    decimal("3.003"),
                     None,
                 ),
                 Tag::ExtXEndList,
@@ -287,11 +312,11 @@ https://media.example.com/third.ts
 #EXT-X-VERSION:6
 #EXT-X-TARGETDURATION:10
 #EXT-X-MAP:URI="init.mp4"
-#EXTINF:5.0050,
+#EXTINF:5.005,
 https://media.example.com/first.ts
-#EXTINF:5.0050,
+#EXTINF:5.005,
 https://media.example.com/second.ts
-#EXTINF:3.0030,
+#EXTINF:3.003,
 https://media.example.com/third.ts
 #EXT-X-ENDLIST
 "#;
@@ -322,20 +347,20 @@ https://media.example.com/third.ts
                 Tag::ExtM3U,
                 Tag::ExtXVersion(7),
                 Tag::ExtXTargetDuration(10),
-                Tag::ExtXProgramDateTime("2020-01-01T00:00:00Z".to_string()),
+                Tag::ExtXProgramDateTime("2020-01-01T00:00:00Z".parse().unwrap()),
                 Tag::ExtInf(
                     "https://media.example.com/first.ts".to_string(),
-                    5.005,
+                    decimal("5.005"),
                     None
                 ),
                 Tag::ExtInf(
                     "https://media.example.com/second.ts".to_string(),
-                    5.005,
+                    decimal("5.005"),
                     None
                 ),
                 Tag::ExtInf(
                     "https://media.example.com/third.ts".to_string(),
-                    3.003,
+                    decimal("3.003"),
                     None
                 ),
                 Tag::ExtXEndList,
@@ -350,20 +375,20 @@ https://media.example.com/third.ts
                 Tag::ExtM3U,
                 Tag::ExtXVersion(7),
                 Tag::ExtXTargetDuration(10),
-                Tag::ExtXProgramDateTime("2020-01-01T00:00:00Z".to_string()),
+                Tag::ExtXProgramDateTime("2020-01-01T00:00:00Z".parse().unwrap()),
                 Tag::ExtInf(
                     "https://media.example.com/first.ts".to_string(),
-                    5.005,
+                    decimal("5.005"),
                     None,
                 ),
                 Tag::ExtInf(
                     "https://media.example.com/second.ts".to_string(),
-                    5.005,
+                    decimal("5.005"),
                     None,
                 ),
                 Tag::ExtInf(
                     "https://media.example.com/third.ts".to_string(),
-                    3.003,
+                    decimal("3.003"),
                     None,
                 ),
                 Tag::ExtXEndList,
@@ -380,11 +405,11 @@ https://media.example.com/third.ts
 #EXT-X-VERSION:7
 #EXT-X-TARGETDURATION:10
 #EXT-X-PROGRAM-DATE-TIME:2020-01-01T00:00:00Z
-#EXTINF:5.0050,
+#EXTINF:5.005,
 https://media.example.com/first.ts
-#EXTINF:5.0050,
+#EXTINF:5.005,
 https://media.example.com/second.ts
-#EXTINF:3.0030,
+#EXTINF:3.003,
 https://media.example.com/third.ts
 #EXT-X-ENDLIST
 "#;
@@ -398,10 +423,13 @@ https://media.example.com/third.ts
 #EXTM3U
 #EXT-X-VERSION:7
 #EXT-X-TARGETDURATION:10
+#EXT-X-PROGRAM-DATE-TIME:2020-01-01T00:00:00Z
+#EXT-X-DATERANGE:ID="splice-6FFFFFF0",CLASS="com.example.ad",START-DATE="2020-01-01T00:00:05Z",PLANNED-DURATION=59.993,X-AD-ID="1234",X-COM-EXAMPLE-BEACON=0xA1B2
 #EXTINF:5.0050,
 https://media.example.com/first.ts
 #EXTINF:5.0050,
 https://media.example.com/second.ts
+#EXT-X-DATERANGE:ID="splice-6FFFFFF0",START-DATE="2020-01-01T00:00:05Z",END-DATE="2020-01-01T00:01:05Z",DURATION=60,CUE="POST"
 #EXTINF:3.0030,
 https://media.example.com/third.ts
 #EXT-X-ENDLIST
@@ -414,221 +442,2896 @@ https://media.example.com/third.ts
                 Tag::ExtM3U,
                 Tag::ExtXVersion(7),
                 Tag::ExtXTargetDuration(10),
+                Tag::ExtXProgramDateTime("2020-01-01T00:00:00Z".parse().unwrap()),
+                Tag::ExtXDateRange {
+                    id: "splice-6FFFFFF0".to_string(),
+                    class: Some("com.example.ad".to_string()),
+                    start_date: "2020-01-01T00:00:05Z".parse().unwrap(),
+                    end_date: None,
+                    duration: None,
+                    planned_duration: Some(59.993),
+                    end_on_next: None,
+                    cue: None,
+                    client_attributes: vec![
+                        (
+                            "X-AD-ID".to_string(),
+                            AttributeValue::QuotedString("1234".to_string())
+                        ),
+                        (
+                            "X-COM-EXAMPLE-BEACON".to_string(),
+                            AttributeValue::HexadecimalSequence("0xA1B2".to_string())
+                        ),
+                    ],
+                    other: Vec::new(),
+                },
                 Tag::ExtInf(
                     "https://media.example.com/first.ts".to_string(),
-                    5.005,
+                    decimal("5.005"),
                     None
                 ),
                 Tag::ExtInf(
                     "https://media.example.com/second.ts".to_string(),
-                    5.005,
+                    decimal("5.005"),
                     None
                 ),
+                Tag::ExtXDateRange {
+                    id: "splice-6FFFFFF0".to_string(),
+                    class: None,
+                    start_date: "2020-01-01T00:00:05Z".parse().unwrap(),
+                    end_date: Some("2020-01-01T00:01:05Z".parse().unwrap()),
+                    duration: Some(60.0),
+                    planned_duration: None,
+                    end_on_next: None,
+                    cue: Some("POST".to_string()),
+                    client_attributes: vec![],
+                    other: Vec::new(),
+                },
                 Tag::ExtInf(
                     "https://media.example.com/third.ts".to_string(),
-                    3.003,
+                    decimal("3.003"),
                     None
                 ),
                 Tag::ExtXEndList,
             ]
         );
+        assert!(playlist.validate().is_ok());
+        assert_eq!(
+            playlist.tags[4].to_string(),
+            r#"#EXT-X-DATERANGE:ID="splice-6FFFFFF0",CLASS="com.example.ad",START-DATE="2020-01-01T00:00:05Z",PLANNED-DURATION=59.993,X-AD-ID="1234",X-COM-EXAMPLE-BEACON=0xA1B2"#
+        );
     }
 
     #[test]
-    fn test_playlist_builder() {
-        let playlist = PlaylistBuilder::new()
-            .extm3u()
-            .version(7)
-            .target_duration(10)
-            .extinf("https://media.example.com/first.ts", 5.005, None)
-            .extinf("https://media.example.com/second.ts", 5.005, None)
-            .extinf("https://media.example.com/third.ts", 3.003, None)
-            .end_list()
-            .build()
-            .unwrap();
+    fn test_parse_playlist_with_hash_in_uri() {
+        let data = "\u{feff}#EXTM3U\r\n#EXT-X-TARGETDURATION:10\r\n# a plain comment\r\n#EXTINF:5.005,Intro\r\nhttps://cdn.example.com/first.ts?token=a#b\r\n#EXTINF:5.005,\r\nsecond.ts#t=10\r\n#EXT-X-ENDLIST\r\n";
 
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
         assert_eq!(
             playlist.tags,
             vec![
                 Tag::ExtM3U,
-                Tag::ExtXVersion(7),
                 Tag::ExtXTargetDuration(10),
+                Tag::Comment(" a plain comment".to_string()),
                 Tag::ExtInf(
-                    "https://media.example.com/first.ts".to_string(),
-                    5.005,
-                    None
-                ),
-                Tag::ExtInf(
-                    "https://media.example.com/second.ts".to_string(),
-                    5.005,
-                    None
-                ),
-                Tag::ExtInf(
-                    "https://media.example.com/third.ts".to_string(),
-                    3.003,
-                    None
+                    "https://cdn.example.com/first.ts?token=a#b".to_string(),
+                    decimal("5.005"),
+                    Some("Intro".to_string())
                 ),
+                Tag::ExtInf("second.ts#t=10".to_string(), decimal("5.005"), None),
                 Tag::ExtXEndList,
             ]
         );
-
-        let mut output = Vec::new();
-        for tag in &playlist.tags {
-            writeln!(output, "{}", tag).unwrap();
-        }
-        let output = String::from_utf8(output).unwrap();
-
-        let expected = "#EXTM3U
-#EXT-X-VERSION:7
-#EXT-X-TARGETDURATION:10
-#EXTINF:5.0050,
-https://media.example.com/first.ts
-#EXTINF:5.0050,
-https://media.example.com/second.ts
-#EXTINF:3.0030,
-https://media.example.com/third.ts
-#EXT-X-ENDLIST
-";
-
-        assert_eq!(output, expected);
-    }
-
-    #[test]
-    fn test_validate_playlist() {
-        let playlist = PlaylistBuilder::new()
-            .extm3u()
-            .version(3)
-            .target_duration(10)
-            .extinf("https://media.example.com/first.ts", 5.005, None)
-            .extinf("https://media.example.com/second.ts", 5.005, None)
-            .extinf("https://media.example.com/third.ts", 3.003, None)
-            .end_list()
-            .build();
-
-        assert!(playlist.is_ok());
-    }
-
-    #[test]
-    fn test_validate_playlist_missing_extm3u() {
-        let playlist = PlaylistBuilder::new()
-            .version(3)
-            .target_duration(10)
-            .extinf("https://media.example.com/first.ts", 5.005, None)
-            .extinf("https://media.example.com/second.ts", 5.005, None)
-            .extinf("https://media.example.com/third.ts", 3.003, None)
-            .end_list()
-            .build();
-
-        assert_eq!(playlist, Err(vec![ValidationError::MissingExtM3U]));
     }
 
     #[test]
-    fn test_validate_playlist_invalid_version() {
-        let playlist = PlaylistBuilder::new()
-            .extm3u()
-            .version(8) // Invalid version
-            .target_duration(10)
-            .extinf("https://media.example.com/first.ts", 5.005, None)
-            .extinf("https://media.example.com/second.ts", 5.005, None)
-            .extinf("https://media.example.com/third.ts", 3.003, None)
-            .end_list()
-            .build();
+    fn test_parse_playlist_with_stream_inf_uri() {
+        let data = r#"
+#EXTM3U
+#EXT-X-STREAM-INF:BANDWIDTH=500000,RESOLUTION=640x360,CODECS="avc1.42c01e,mp4a.40.2"
+low/index.m3u8
+"#;
 
-        assert_eq!(playlist, Err(vec![ValidationError::InvalidVersion(8)]));
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+        assert_eq!(
+            playlist.tags,
+            vec![
+                Tag::ExtM3U,
+                Tag::ExtXStreamInf {
+                    bandwidth: 500000,
+                    codecs: Some("avc1.42c01e,mp4a.40.2".to_string()),
+                    resolution: Some(Resolution {
+                        width: 640,
+                        height: 360,
+                    }),
+                    frame_rate: None,
+                    audio: None,
+                    video: None,
+                    subtitle: None,
+                    closed_captions: None,
+                    average_bandwidth: None,
+                    hdcp_level: None,
+                    video_range: None,
+                    score: None,
+                    supplemental_codecs: None,
+                    allowed_cpc: None,
+                    stable_variant_id: None,
+                    pathway_id: None,
+                    req_video_layout: None,
+                    uri: "low/index.m3u8".to_string(),
+                    other: Vec::new(),
+                },
+            ]
+        );
     }
 
     #[test]
-    fn test_validate_playlist_invalid_duration() {
-        let playlist = PlaylistBuilder::new()
-            .extm3u()
-            .version(3)
-            .target_duration(10)
-            .extinf("https://media.example.com/first.ts", -5.005, None) // Invalid duration
-            .extinf("https://media.example.com/second.ts", 5.005, None)
-            .extinf("https://media.example.com/third.ts", 3.003, None)
-            .end_list()
-            .build();
+    fn test_parse_stream_inf_attributes_in_any_order() {
+        let data = r#"
+#EXTM3U
+#EXT-X-STREAM-INF:CODECS="avc1.640028,mp4a.40.2",AUDIO="aac",BANDWIDTH=2500000,FRAME-RATE=29.970,SUBTITLES="subs",CLOSED-CAPTIONS="cc",VIDEO="main"
+mid/index.m3u8
+#EXT-X-STREAM-INF:CLOSED-CAPTIONS=NONE,BANDWIDTH=64000
+audio-only/index.m3u8
+"#;
 
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
         assert_eq!(
-            playlist,
-            Err(vec![ValidationError::InvalidDuration(-5.005)])
+            playlist.tags,
+            vec![
+                Tag::ExtM3U,
+                Tag::ExtXStreamInf {
+                    bandwidth: 2500000,
+                    codecs: Some("avc1.640028,mp4a.40.2".to_string()),
+                    resolution: None,
+                    frame_rate: Some(29.97),
+                    audio: Some("aac".to_string()),
+                    video: Some("main".to_string()),
+                    subtitle: Some("subs".to_string()),
+                    closed_captions: Some("cc".to_string()),
+                    average_bandwidth: None,
+                    hdcp_level: None,
+                    video_range: None,
+                    score: None,
+                    supplemental_codecs: None,
+                    allowed_cpc: None,
+                    stable_variant_id: None,
+                    pathway_id: None,
+                    req_video_layout: None,
+                    uri: "mid/index.m3u8".to_string(),
+                    other: Vec::new(),
+                },
+                Tag::ExtXStreamInf {
+                    bandwidth: 64000,
+                    codecs: None,
+                    resolution: None,
+                    frame_rate: None,
+                    audio: None,
+                    video: None,
+                    subtitle: None,
+                    closed_captions: Some("NONE".to_string()),
+                    average_bandwidth: None,
+                    hdcp_level: None,
+                    video_range: None,
+                    score: None,
+                    supplemental_codecs: None,
+                    allowed_cpc: None,
+                    stable_variant_id: None,
+                    pathway_id: None,
+                    req_video_layout: None,
+                    uri: "audio-only/index.m3u8".to_string(),
+                    other: Vec::new(),
+                },
+            ]
+        );
+        assert_eq!(
+            playlist.tags[2].to_string(),
+            "#EXT-X-STREAM-INF:BANDWIDTH=64000,CLOSED-CAPTIONS=NONE\naudio-only/index.m3u8"
         );
     }
 
     #[test]
-    fn test_validate_playlist_invalid_target_duration() {
-        let playlist = PlaylistBuilder::new()
+    fn test_stream_inf_full_attribute_round_trip() {
+        let line = r#"#EXT-X-STREAM-INF:BANDWIDTH=9000000,AVERAGE-BANDWIDTH=7500000,CODECS="hvc1.2.4.L150.B0,ec-3",SUPPLEMENTAL-CODECS="dvh1.08.07/db4h",RESOLUTION=3840x2160,FRAME-RATE=23.976,HDCP-LEVEL=TYPE-1,VIDEO-RANGE=PQ,SCORE=2.5,ALLOWED-CPC="com.example.drm:SMART-TV/PC",STABLE-VARIANT-ID="uhd-hdr",REQ-VIDEO-LAYOUT="CH-MONO",AUDIO="atmos",CLOSED-CAPTIONS=NONE,PATHWAY-ID="cdn-a"
+uhd/index.m3u8"#;
+        let data = format!(
+            "#EXTM3U\n#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"atmos\",NAME=\"English\"\n{}\n",
+            line
+        );
+
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+        let expected = PlaylistBuilder::new()
             .extm3u()
-            .version(3)
-            .target_duration(0) // Invalid target duration
-            .extinf("https://media.example.com/first.ts", 5.005, None)
-            .extinf("https://media.example.com/second.ts", 5.005, None)
-            .extinf("https://media.example.com/third.ts", 3.003, None)
-            .end_list()
-            .build();
+            .media(
+                MediaType::Audio,
+                "atmos",
+                Some("English"),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .stream_inf(
+                "uhd/index.m3u8",
+                9000000,
+                Some("hvc1.2.4.L150.B0,ec-3"),
+                Some(Resolution {
+                    width: 3840,
+                    height: 2160,
+                }),
+                Some(23.976),
+                Some("atmos"),
+                None,
+                None,
+                Some("NONE"),
+                Some(7500000),
+                Some("TYPE-1"),
+                Some("PQ"),
+                Some(2.5),
+                Some("dvh1.08.07/db4h"),
+                Some("com.example.drm:SMART-TV/PC"),
+                Some("uhd-hdr"),
+                Some("cdn-a"),
+                Some("CH-MONO"),
+            )
+            .build()
+            .unwrap();
 
-        assert_eq!(
-            playlist,
-            Err(vec![ValidationError::InvalidTargetDuration(0)])
-        );
+        assert_eq!(playlist, expected);
+        assert_eq!(playlist.tags[2].to_string(), line);
     }
 
     #[test]
-    fn test_validate_playlist_invalid_key_method() {
-        let playlist = PlaylistBuilder::new()
+    fn test_parse_media_renditions() {
+        let data = r#"
+#EXTM3U
+#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID="aac",NAME="English",LANGUAGE="en",DEFAULT=YES,AUTOSELECT=YES,CHANNELS="2",URI="audio/en.m3u8"
+#EXT-X-MEDIA:GROUP-ID="cc",TYPE=CLOSED-CAPTIONS,NAME="CC1",INSTREAM-ID="CC1"
+#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID="lossless",NAME="Français",LANGUAGE="fr",ASSOC-LANGUAGE="fr-CA",CHARACTERISTICS="public.accessibility.describes-video",STABLE-RENDITION-ID="fr-ad",BIT-DEPTH=24,SAMPLE-RATE=48000,URI="audio/fr.m3u8"
+"#;
+
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+        let expected = PlaylistBuilder::new()
             .extm3u()
-            .version(3)
-            .target_duration(10)
-            .key(
-                "INVALID-METHOD", // Invalid key method
-                Some("https://priv.example.com/key.php?r=52"),
+            .media(
+                MediaType::Audio,
+                "aac",
+                Some("English"),
+                Some("audio/en.m3u8"),
+                Some(true),
+                Some(true),
+                None,
+                Some("en"),
+                None,
+                None,
+                None,
+                None,
+                Some("2"),
                 None,
                 None,
                 None,
             )
-            .extinf("https://media.example.com/first.ts", 5.005, None)
-            .extinf("https://media.example.com/second.ts", 5.005, None)
-            .extinf("https://media.example.com/third.ts", 3.003, None)
-            .end_list()
-            .build();
+            .media(
+                MediaType::ClosedCaptions,
+                "cc",
+                Some("CC1"),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some("CC1"),
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .media(
+                MediaType::Audio,
+                "lossless",
+                Some("Français"),
+                Some("audio/fr.m3u8"),
+                None,
+                None,
+                Some("public.accessibility.describes-video"),
+                Some("fr"),
+                None,
+                None,
+                None,
+                Some("fr-CA"),
+                None,
+                Some("fr-ad"),
+                Some(24),
+                Some(48000),
+            )
+            .build()
+            .unwrap();
+        assert_eq!(playlist, expected);
 
-        assert_eq!(
-            playlist,
-            Err(vec![ValidationError::InvalidKeyMethod(
-                "INVALID-METHOD".to_string()
-            )])
-        );
+        let output = playlist.tags[1].to_string();
+        assert!(output.contains(",AUTOSELECT=YES"));
+        assert!(!output.contains("AUTOPLAY"));
+        for tag in &playlist.tags {
+            let reparsed = Playlist::from_reader(format!("{}\n", tag).as_bytes()).unwrap();
+            assert_eq!(reparsed.tags, vec![tag.clone()]);
+        }
     }
 
     #[test]
-    fn test_validate_playlist_invalid_map_uri() {
-        let playlist = PlaylistBuilder::new()
-            .extm3u()
-            .version(3)
-            .target_duration(10)
-            .map("", None) // Invalid map URI
-            .extinf("https://media.example.com/first.ts", 5.005, None)
-            .extinf("https://media.example.com/second.ts", 5.005, None)
-            .extinf("https://media.example.com/third.ts", 3.003, None)
-            .end_list()
-            .build();
+    fn test_parse_playlist_with_missing_segment_uri() {
+        let data = "#EXTM3U\n#EXT-X-TARGETDURATION:10\n#EXTINF:5.005,\n#EXT-X-ENDLIST\n";
 
-        assert_eq!(playlist, Err(vec![ValidationError::InvalidMapUri]));
+        assert!(Playlist::from_reader(data.as_bytes()).is_err());
     }
 
     #[test]
-    fn test_validate_playlist_invalid_program_date_time() {
-        let playlist = PlaylistBuilder::new()
-            .extm3u()
-            .version(3)
-            .target_duration(10)
-            .program_date_time("") // Invalid program date time
-            .extinf("https://media.example.com/first.ts", 5.005, None)
-            .extinf("https://media.example.com/second.ts", 5.005, None)
-            .extinf("https://media.example.com/third.ts", 3.003, None)
+    fn test_parse_attributes_typed_values() {
+        let attributes = parse_attributes(
+            r#"BANDWIDTH=1280000,CODECS="avc1.64001f,mp4a.40.2",RESOLUTION=1920x1080,FRAME-RATE=29.970,IV=0x9c7db8778570d05c,TIME-OFFSET=-12.5,DEFAULT=YES"#,
+        )
+        .unwrap();
+
+        let values: Vec<_> = attributes
+            .iter()
+            .map(|attribute| (attribute.name.as_str(), attribute.value.clone()))
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                ("BANDWIDTH", AttributeValue::DecimalInteger(1280000)),
+                (
+                    "CODECS",
+                    AttributeValue::QuotedString("avc1.64001f,mp4a.40.2".to_string())
+                ),
+                ("RESOLUTION", AttributeValue::DecimalResolution(1920, 1080)),
+                ("FRAME-RATE", AttributeValue::DecimalFloatingPoint(29.97)),
+                (
+                    "IV",
+                    AttributeValue::HexadecimalSequence("0x9c7db8778570d05c".to_string())
+                ),
+                (
+                    "TIME-OFFSET",
+                    AttributeValue::SignedDecimalFloatingPoint(-12.5)
+                ),
+                (
+                    "DEFAULT",
+                    AttributeValue::EnumeratedString("YES".to_string())
+                ),
+            ]
+        );
+        assert!(attributes.decimal_integer("CODECS").is_err());
+        assert_eq!(attributes.decimal_float("BANDWIDTH"), Ok(Some(1280000.0)));
+        assert_eq!(attributes.yes_no("DEFAULT"), Ok(Some(true)));
+    }
+
+    #[test]
+    fn test_parse_attributes_malformed() {
+        assert!(parse_attributes(r#"URI="unterminated"#).is_err());
+        assert!(parse_attributes("BANDWIDTH").is_err());
+        assert!(parse_attributes("bandwidth=1").is_err());
+        assert!(parse_attributes("BANDWIDTH=1,BANDWIDTH=2").is_err());
+        assert!(parse_attributes("BANDWIDTH=1,").is_err());
+        assert!(parse_attributes(r#"URI="a"b"#).is_err());
+        assert!(parse_attributes("IV=0xZZ").is_err());
+        assert_eq!(parse_attributes("").map(|list| list.len()), Ok(0));
+    }
+
+    #[test]
+    fn test_parse_playlist_with_attribute_lists() {
+        let data = r#"
+#EXTM3U
+#EXT-X-TARGETDURATION:10
+#EXT-X-KEY:URI="https://priv.example.com/key?a=1,b=2",IV=0x9c7db8778570d05c0123456789abcdef,METHOD=AES-128
+#EXT-X-START:PRECISE=YES,TIME-OFFSET=-12.5
+#EXTINF:5.005,
+https://media.example.com/first.ts
+"#;
+
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+        assert_eq!(
+            playlist.tags,
+            vec![
+                Tag::ExtM3U,
+                Tag::ExtXTargetDuration(10),
+                Tag::ExtXKey {
+                    method: KeyMethod::Aes128,
+                    uri: Some("https://priv.example.com/key?a=1,b=2".to_string()),
+                    iv: Some(InitializationVector::new(
+                        0x9c7db8778570d05c0123456789abcdef
+                    )),
+                    keyformat: None,
+                    keyformatversions: None,
+                    other: Vec::new(),
+                },
+                Tag::ExtXStart {
+                    time_offset: TimeOffset(-12.5),
+                    precise: Some(true),
+                    other: Vec::new(),
+                },
+                Tag::ExtInf(
+                    "https://media.example.com/first.ts".to_string(),
+                    decimal("5.005"),
+                    None
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_playlist_with_malformed_attribute_list() {
+        let data = "#EXTM3U\n#EXT-X-KEY:URI=\"https://priv.example.com/key\"\n";
+        assert!(Playlist::from_reader(data.as_bytes()).is_err());
+
+        let data = "#EXTM3U\n#EXT-X-MAP:URI=\"init.mp4\",BYTERANGE=\"800@0\n";
+        assert!(Playlist::from_reader(data.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_parse_session_data() {
+        let data = r#"#EXTM3U
+#EXT-X-SESSION-DATA:DATA-ID="com.example.title",VALUE="Example",LANGUAGE="en"
+#EXT-X-SESSION-DATA:DATA-ID="com.example.lyrics",URI="lyrics.json"
+#EXT-X-STREAM-INF:BANDWIDTH=1280000
+low.m3u8
+"#;
+
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+        assert_eq!(
+            playlist.tags[1..3],
+            [
+                Tag::ExtXSessionData {
+                    id: "com.example.title".to_string(),
+                    value: Some("Example".to_string()),
+                    uri: None,
+                    language: Some("en".to_string()),
+                    other: Vec::new(),
+                },
+                Tag::ExtXSessionData {
+                    id: "com.example.lyrics".to_string(),
+                    value: None,
+                    uri: Some("lyrics.json".to_string()),
+                    language: None,
+                    other: Vec::new(),
+                },
+            ]
+        );
+        assert_eq!(playlist.to_string(), data);
+
+        for attributes in [
+            "DATA-ID=\"com.example.title\"",
+            "DATA-ID=\"com.example.title\",VALUE=\"Example\",URI=\"title.json\"",
+        ] {
+            let data = format!("#EXTM3U\n#EXT-X-SESSION-DATA:{}\n", attributes);
+            let error = Playlist::from_reader(data.as_bytes()).unwrap_err();
+            assert!(matches!(
+                error.kind(),
+                Some(ParseErrorKind::InvalidTagValue(_))
+            ));
+        }
+
+        let playlist = Playlist {
+            tags: vec![
+                Tag::ExtM3U,
+                Tag::ExtXSessionData {
+                    id: "com.example.title".to_string(),
+                    value: None,
+                    uri: None,
+                    language: None,
+                    other: Vec::new(),
+                },
+            ],
+        };
+        assert_eq!(
+            playlist.validate(),
+            Err(vec![ValidationError::InvalidSessionData(
+                "com.example.title".to_string()
+            )])
+        );
+    }
+
+    #[test]
+    fn test_parse_error_version_out_of_range() {
+        let data = "#EXTM3U\n#EXT-X-VERSION:999\n";
+
+        match Playlist::from_reader(data.as_bytes()) {
+            Err(ParseError::Syntax {
+                line,
+                column,
+                raw,
+                kind: ParseErrorKind::InvalidTagValue(_),
+            }) => {
+                assert_eq!(line, 2);
+                assert_eq!(column, 16);
+                assert_eq!(raw, "#EXT-X-VERSION:999");
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_error_locates_attribute() {
+        let data =
+            "#EXTM3U\r\n  #EXT-X-STREAM-INF:BANDWIDTH=1280000,RESOLUTION=wide\r\nlow.m3u8\r\n";
+
+        let error = Playlist::from_reader(data.as_bytes()).unwrap_err();
+        match &error {
+            ParseError::Syntax {
+                line, column, kind, ..
+            } => {
+                assert_eq!(*line, 2);
+                assert_eq!(*column, 39);
+                assert_eq!(
+                    *kind,
+                    ParseErrorKind::InvalidAttributeValue {
+                        name: "RESOLUTION".to_string(),
+                        reason: "unexpected type enumerated-string".to_string(),
+                    }
+                );
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+        assert!(error.to_string().starts_with("line 2, column 39: "));
+    }
+
+    #[test]
+    fn test_parse_error_missing_uri_and_attribute() {
+        let data = "#EXTM3U\n#EXTINF:5.005,\n#EXTINF:5.005,\nsecond.ts\n";
+        let error = Playlist::from_reader(data.as_bytes()).unwrap_err();
+        assert_eq!(error.line(), Some(2));
+        assert_eq!(error.kind(), Some(&ParseErrorKind::MissingUri));
+
+        let data = "#EXTM3U\n#EXT-X-MAP:BYTERANGE=\"800@0\"\n";
+        let error = Playlist::from_reader(data.as_bytes()).unwrap_err();
+        assert_eq!(
+            error.kind(),
+            Some(&ParseErrorKind::MissingAttribute("URI".to_string()))
+        );
+
+        let data = "#EXTM3U\nfirst.ts\n";
+        let error = Playlist::from_reader(data.as_bytes()).unwrap_err();
+        assert_eq!(error.kind(), Some(&ParseErrorKind::UnexpectedUri));
+
+        let error = Playlist::from_file("does/not/exist.m3u8").unwrap_err();
+        assert!(matches!(error, ParseError::Io(_)));
+    }
+
+    #[test]
+    fn test_unknown_tags_and_comments_round_trip() {
+        let data = "#EXTM3U
+#EXT-X-TARGETDURATION:10
+#EXT-X-ALLOW-CACHE:YES
+# Generated by packager v2
+#EXT-X-CUE-OUT:DURATION=30
+#EXT-X-SCTE35:CUE=\"/DAlAAAAAAAAAP/wFAUAAAABf+/+ANgNkv4AFJlwAAEBAQAA5xULLA==\"
+#EXTINF:10,
+first.ts
+#EXT-X-CUE-IN
+#EXT-X-GAP
+#EXTINF:10,
+second.ts
+#EXT-X-ENDLIST
+";
+
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+        assert_eq!(
+            playlist.tags[2..6],
+            [
+                Tag::Unknown {
+                    name: "EXT-X-ALLOW-CACHE".to_string(),
+                    value: Some("YES".to_string()),
+                },
+                Tag::Comment(" Generated by packager v2".to_string()),
+                Tag::Unknown {
+                    name: "EXT-X-CUE-OUT".to_string(),
+                    value: Some("DURATION=30".to_string()),
+                },
+                Tag::Unknown {
+                    name: "EXT-X-SCTE35".to_string(),
+                    value: Some(
+                        "CUE=\"/DAlAAAAAAAAAP/wFAUAAAABf+/+ANgNkv4AFJlwAAEBAQAA5xULLA==\""
+                            .to_string()
+                    ),
+                },
+            ]
+        );
+        assert_eq!(
+            playlist.tags[7],
+            Tag::Unknown {
+                name: "EXT-X-CUE-IN".to_string(),
+                value: None,
+            }
+        );
+        assert_eq!(playlist.tags[8], Tag::ExtXGap);
+
+        let mut output = Vec::new();
+        for tag in &playlist.tags {
+            writeln!(output, "{}", tag).unwrap();
+        }
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output, data);
+    }
+
+    #[test]
+    fn test_low_latency_playlist_round_trip() {
+        let data = r#"#EXTM3U
+#EXT-X-TARGETDURATION:4
+#EXT-X-VERSION:6
+#EXT-X-SERVER-CONTROL:CAN-BLOCK-RELOAD=YES,PART-HOLD-BACK=1.0,CAN-SKIP-UNTIL=24.0
+#EXT-X-PART-INF:PART-TARGET=0.33334
+#EXT-X-MEDIA-SEQUENCE:266
+#EXT-X-PROGRAM-DATE-TIME:2019-02-14T02:13:36.106Z
+#EXT-X-MAP:URI="init.mp4"
+#EXT-X-DATERANGE:ID="splice-6FFFFFF0",START-DATE="2019-02-14T02:13:40.106Z",PLANNED-DURATION=59.993,SCTE35-OUT=0xFC002F0000000000FF0000
+#EXTINF:4.00008,
+fileSequence266.mp4
+#EXTINF:4.00008,
+fileSequence267.mp4
+#EXT-X-PART:DURATION=0.33334,URI="filePart268.0.mp4",INDEPENDENT=YES
+#EXT-X-PART:DURATION=0.33334,URI="filePart268.1.mp4"
+#EXT-X-PART:DURATION=0.33334,URI="filePart268.2.mp4",GAP=YES
+#EXTINF:1.00002,
+fileSequence268.mp4
+#EXT-X-PART:DURATION=0.33334,URI="filePart269.0.mp4",INDEPENDENT=YES
+#EXT-X-PRELOAD-HINT:TYPE=PART,URI="filePart269.1.mp4",BYTERANGE-START=0
+#EXT-X-RENDITION-REPORT:URI="../1M/waitForMSN.php",LAST-MSN=269,LAST-PART=0
+#EXT-X-RENDITION-REPORT:URI="../4M/waitForMSN.php",LAST-MSN=269,LAST-PART=0
+"#;
+
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+        assert!(!playlist
+            .tags
+            .iter()
+            .any(|tag| matches!(tag, Tag::Unknown { .. })));
+        assert!(matches!(
+            &playlist.tags[8],
+            Tag::ExtXDateRange { other, .. } if *other == [(
+                "SCTE35-OUT".to_string(),
+                AttributeValue::HexadecimalSequence("0xFC002F0000000000FF0000".to_string())
+            )]
+        ));
+        assert!(matches!(
+            &playlist.tags[13],
+            Tag::ExtXPart { other, .. }
+                if *other == [("GAP".to_string(), AttributeValue::EnumeratedString("YES".to_string()))]
+        ));
+        assert!(matches!(
+            &playlist.tags[16],
+            Tag::ExtXPreloadHint { other, .. }
+                if *other == [("BYTERANGE-START".to_string(), AttributeValue::DecimalInteger(0))]
+        ));
+        assert_eq!(
+            playlist.tags[17],
+            Tag::ExtXRenditionReport {
+                uri: "../1M/waitForMSN.php".to_string(),
+                last_msn: Some(269),
+                last_part: Some(0),
+                bandwidth: None,
+                other: Vec::new(),
+            }
+        );
+
+        // Attributes are written in the order each tag writes them, so compare the
+        // parsed tags rather than the text.
+        let written = playlist.to_string();
+        assert!(written.contains(",GAP=YES\n"));
+        assert_eq!(Playlist::from_reader(written.as_bytes()).unwrap(), playlist);
+        assert_eq!(Playlist::from(&MediaPlaylist::from(&playlist)), playlist);
+
+        let data = r#"#EXTM3U
+#EXT-X-STREAM-INF:PROGRAM-ID=1,BANDWIDTH=1280000,CODECS="avc1.4d401f,mp4a.40.2"
+low.m3u8
+#EXT-X-I-FRAME-STREAM-INF:BANDWIDTH=86000,VIDEO-RANGE=SDR,URI="low/iframe.m3u8"
+"#;
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+        let master = MasterPlaylist::from(&playlist);
+        assert_eq!(
+            master.variants[0].other,
+            [("PROGRAM-ID".to_string(), AttributeValue::DecimalInteger(1))]
+        );
+        assert_eq!(
+            master.iframe_variants[0].other,
+            [(
+                "VIDEO-RANGE".to_string(),
+                AttributeValue::EnumeratedString("SDR".to_string())
+            )]
+        );
+        let written = Playlist::from(&master).to_string();
+        assert!(written.contains(",PROGRAM-ID=1\n"));
+        assert_eq!(Playlist::from_reader(written.as_bytes()).unwrap(), playlist);
+
+        let data = "#EXTM3U\n#EXT-X-VERSION:4\n#EXT-X-I-FRAMES-ONLY\n#EXT-X-TARGETDURATION:10\n";
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+        assert_eq!(playlist.tags[2], Tag::ExtXIFramesOnly);
+        assert_eq!(playlist.required_version(), 4);
+        assert_eq!(playlist.to_string(), data);
+    }
+
+    #[test]
+    fn test_media_playlist_resolves_segments() {
+        let data = r#"#EXTM3U
+#EXT-X-TARGETDURATION:10
+#EXT-X-MEDIA-SEQUENCE:7
+#EXT-X-MAP:URI="init.mp4"
+#EXT-X-KEY:METHOD=AES-128,URI="key1.bin"
+#EXT-X-PROGRAM-DATE-TIME:2020-01-01T00:00:00Z
+#EXTINF:9.009,
+first.ts
+#EXT-X-BYTERANGE:1000@0
+#EXTINF:9.009,
+second.ts
+#EXT-X-DISCONTINUITY
+#EXT-X-KEY:METHOD=NONE
+#EXTINF:3.003,
+third.ts
+#EXT-X-PART:DURATION=1.0,URI="part1.mp4"
+#EXT-X-ENDLIST
+"#;
+
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+        let media = MediaPlaylist::from(&playlist);
+
+        assert_eq!(media.header.len(), 3);
+        assert_eq!(media.target_duration(), Some(10));
+        assert_eq!(media.media_sequence(), 7);
+        assert!(media.is_ended());
+        assert_eq!(media.segments.len(), 3);
+
+        let first = &media.segments[0];
+        assert_eq!(first.uri, "first.ts");
+        assert_eq!(
+            first.key.as_ref().map(|key| key.uri.as_deref()),
+            Some(Some("key1.bin"))
+        );
+        assert_eq!(
+            first.map.as_ref().map(|map| map.uri.as_str()),
+            Some("init.mp4")
+        );
+        assert_eq!(
+            first.program_date_time,
+            Some("2020-01-01T00:00:00Z".parse().unwrap())
+        );
+        assert_eq!(first.byte_range, None);
+
+        let second = &media.segments[1];
+        assert_eq!(
+            second.byte_range,
+            Some(ByteRange {
+                length: 1000,
+                offset: Some(0)
+            })
+        );
+        assert_eq!(second.key, first.key);
+        assert_eq!(second.map, first.map);
+        assert_eq!(second.program_date_time, None);
+        assert!(!second.discontinuity);
+
+        let third = &media.segments[2];
+        assert!(third.discontinuity);
+        assert_eq!(third.key, None);
+        assert_eq!(third.map, first.map);
+
+        assert_eq!(
+            media.trailer,
+            vec![
+                Tag::ExtXPart {
+                    uri: "part1.mp4".to_string(),
+                    duration: Some(1.0),
+                    independent: None,
+                    other: Vec::new(),
+                },
+                Tag::ExtXEndList,
+            ]
+        );
+        assert_eq!(Playlist::from(&media), playlist);
+    }
+
+    #[test]
+    fn test_media_playlist_writes_edited_segments() {
+        let data = r#"#EXTM3U
+#EXT-X-TARGETDURATION:10
+#EXT-X-MAP:URI="init.mp4"
+#EXT-X-KEY:METHOD=AES-128,URI="key1.bin"
+#EXTINF:9,
+first.ts
+#EXT-X-BYTERANGE:1000@0
+#EXTINF:9,
+second.ts
+#EXT-X-DISCONTINUITY
+#EXT-X-KEY:METHOD=NONE
+#EXTINF:3,
+third.ts
+#EXT-X-ENDLIST
+"#;
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+        let mut media = MediaPlaylist::from(&playlist);
+
+        for segment in &mut media.segments[..2] {
+            segment.key.as_mut().unwrap().uri = Some("key0.bin".to_string());
+        }
+        media.segments[1].byte_range = None;
+        media.segments[1].program_date_time = Some("2020-01-01T00:00:09Z".parse().unwrap());
+        media.segments[2].discontinuity = false;
+        media.segments[2].map = Some(Map {
+            uri: "init2.mp4".to_string(),
+            byterange: None,
+            other: Vec::new(),
+        });
+        media.segments[2].parts.push(Part {
+            uri: "third.0.mp4".to_string(),
+            duration: Some(1.5),
+            independent: Some(true),
+            other: Vec::new(),
+        });
+
+        let playlist = Playlist::from(&media);
+        assert_eq!(
+            playlist.to_string(),
+            r#"#EXTM3U
+#EXT-X-TARGETDURATION:10
+#EXT-X-MAP:URI="init.mp4"
+#EXT-X-KEY:METHOD=AES-128,URI="key0.bin"
+#EXTINF:9,
+first.ts
+#EXT-X-PROGRAM-DATE-TIME:2020-01-01T00:00:09Z
+#EXTINF:9,
+second.ts
+#EXT-X-KEY:METHOD=NONE
+#EXT-X-MAP:URI="init2.mp4"
+#EXT-X-PART:URI="third.0.mp4",DURATION=1.5,INDEPENDENT=YES
+#EXTINF:3,
+third.ts
+#EXT-X-ENDLIST
+"#
+        );
+        let reparsed = MediaPlaylist::from(&playlist);
+        for (reparsed, edited) in reparsed.segments.iter().zip(&media.segments) {
+            let edited = Segment {
+                tags: reparsed.tags.clone(),
+                ..edited.clone()
+            };
+            assert_eq!(*reparsed, edited);
+        }
+
+        media.segments[1].key = None;
+        let playlist = Playlist::from(&media);
+        assert_eq!(MediaPlaylist::from(&playlist).segments[1].key, None);
+        assert_eq!(MediaPlaylist::from(&playlist).segments[2].key, None);
+    }
+
+    #[test]
+    fn test_media_playlist_keeps_repeated_segment_tags() {
+        let data = r#"#EXTM3U
+#EXT-X-TARGETDURATION:10
+#EXT-X-PROGRAM-DATE-TIME:2020-01-01T00:00:00Z
+#EXT-X-BYTERANGE:500@0
+#EXT-X-PROGRAM-DATE-TIME:2020-01-01T00:00:00.000Z
+#EXT-X-BYTERANGE:1000@0
+#EXTINF:9,
+first.ts
+#EXT-X-BYTERANGE:1000@1000
+#EXT-X-BYTERANGE:1000@2000
+#EXTINF:9,
+second.ts
+#EXT-X-ENDLIST
+"#;
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+        let mut media = MediaPlaylist::from(&playlist);
+        assert_eq!(
+            media.segments[0].byte_range,
+            Some(ByteRange {
+                length: 1000,
+                offset: Some(0),
+            })
+        );
+        assert_eq!(
+            media.segments[0].program_date_time,
+            Some(date_time("2020-01-01T00:00:00.000Z"))
+        );
+        assert_eq!(Playlist::from(&media), playlist);
+
+        // Only the tags of the edited field are rewritten.
+        media.segments[1].byte_range = Some(ByteRange {
+            length: 1000,
+            offset: Some(3000),
+        });
+        assert_eq!(
+            Playlist::from(&media).to_string(),
+            data.replace(
+                "#EXT-X-BYTERANGE:1000@1000\n#EXT-X-BYTERANGE:1000@2000\n",
+                "#EXT-X-BYTERANGE:1000@3000\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_master_playlist_round_trip() {
+        let data = r#"#EXTM3U
+#EXT-X-INDEPENDENT-SEGMENTS
+#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID="aac",NAME="English",DEFAULT=YES,URI="en.m3u8"
+#EXT-X-STREAM-INF:BANDWIDTH=1280000,RESOLUTION=640x360,AUDIO="aac"
+low.m3u8
+#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID="aac",NAME="Deutsch",URI="de.m3u8"
+#EXT-X-I-FRAME-STREAM-INF:BANDWIDTH=86000,URI="low-iframe.m3u8"
+#EXT-X-STREAM-INF:BANDWIDTH=2560000,AUDIO="aac"
+high.m3u8
+"#;
+
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+        let mut master = MasterPlaylist::from(&playlist);
+
+        assert_eq!(master.tags, vec![Tag::ExtM3U, Tag::ExtXIndependentSegments]);
+        assert_eq!(master.variants.len(), 2);
+        assert_eq!(master.variants[0].uri, "low.m3u8");
+        assert_eq!(
+            master.variants[0]
+                .resolution
+                .map(|resolution| resolution.to_string()),
+            Some("640x360".to_string())
+        );
+        assert_eq!(master.variants[1].bandwidth, 2560000);
+        assert_eq!(master.group("aac").count(), 2);
+        assert_eq!(master.renditions[0].default, Some(true));
+        assert_eq!(master.iframe_variants[0].uri, "low-iframe.m3u8");
+        assert_eq!(Playlist::from(&master), playlist);
+
+        // Equality ignores the order the items were parsed in.
+        let mut by_hand = MasterPlaylist::default();
+        by_hand.variants = master.variants.clone();
+        by_hand.renditions = master.renditions.clone();
+        by_hand.iframe_variants = master.iframe_variants.clone();
+        by_hand.tags = master.tags.clone();
+        assert_eq!(by_hand, master);
+        assert_ne!(Playlist::from(&by_hand), playlist);
+
+        master.variants.push(Variant {
+            uri: "extra.m3u8".to_string(),
+            bandwidth: 5000000,
+            ..Default::default()
+        });
+        let tags = Playlist::from(&master).tags;
+        assert_eq!(tags.len(), playlist.tags.len() + 1);
+        assert!(matches!(
+            tags.last(),
+            Some(Tag::ExtXStreamInf { uri, .. }) if uri == "extra.m3u8"
+        ));
+    }
+
+    #[test]
+    fn test_playlist_builder() {
+        let playlist = PlaylistBuilder::new()
+            .extm3u()
+            .version(7)
+            .target_duration(10)
+            .extinf("https://media.example.com/first.ts", 5.005, None)
+            .extinf("https://media.example.com/second.ts", 5.005, None)
+            .extinf("https://media.example.com/third.ts", 3.003, None)
+            .end_list()
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            playlist.tags,
+            vec![
+                Tag::ExtM3U,
+                Tag::ExtXVersion(7),
+                Tag::ExtXTargetDuration(10),
+                Tag::ExtInf(
+                    "https://media.example.com/first.ts".to_string(),
+                    decimal("5.005"),
+                    None
+                ),
+                Tag::ExtInf(
+                    "https://media.example.com/second.ts".to_string(),
+                    decimal("5.005"),
+                    None
+                ),
+                Tag::ExtInf(
+                    "https://media.example.com/third.ts".to_string(),
+                    decimal("3.003"),
+                    None
+                ),
+                Tag::ExtXEndList,
+            ]
+        );
+
+        let mut output = Vec::new();
+        for tag in &playlist.tags {
+            writeln!(output, "{}", tag).unwrap();
+        }
+        let output = String::from_utf8(output).unwrap();
+
+        let expected = "#EXTM3U
+#EXT-X-VERSION:7
+#EXT-X-TARGETDURATION:10
+#EXTINF:5.005,
+https://media.example.com/first.ts
+#EXTINF:5.005,
+https://media.example.com/second.ts
+#EXTINF:3.003,
+https://media.example.com/third.ts
+#EXT-X-ENDLIST
+";
+
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_validate_playlist() {
+        let playlist = PlaylistBuilder::new()
+            .extm3u()
+            .version(3)
+            .target_duration(10)
+            .extinf("https://media.example.com/first.ts", 5.005, None)
+            .extinf("https://media.example.com/second.ts", 5.005, None)
+            .extinf("https://media.example.com/third.ts", 3.003, None)
+            .end_list()
+            .build();
+
+        assert!(playlist.is_ok());
+    }
+
+    #[test]
+    fn test_validate_playlist_missing_extm3u() {
+        let playlist = PlaylistBuilder::new()
+            .version(3)
+            .target_duration(10)
+            .extinf("https://media.example.com/first.ts", 5.005, None)
+            .extinf("https://media.example.com/second.ts", 5.005, None)
+            .extinf("https://media.example.com/third.ts", 3.003, None)
+            .end_list()
+            .build();
+
+        assert_eq!(playlist, Err(vec![ValidationError::MissingExtM3U]));
+    }
+
+    #[test]
+    fn test_validate_playlist_invalid_version() {
+        let playlist = PlaylistBuilder::new()
+            .extm3u()
+            .version(8) // Invalid version
+            .target_duration(10)
+            .extinf("https://media.example.com/first.ts", 5.005, None)
+            .extinf("https://media.example.com/second.ts", 5.005, None)
+            .extinf("https://media.example.com/third.ts", 3.003, None)
+            .end_list()
+            .build();
+
+        assert_eq!(playlist, Err(vec![ValidationError::InvalidVersion(8)]));
+    }
+
+    #[test]
+    fn test_validate_playlist_invalid_duration() {
+        let playlist = PlaylistBuilder::new()
+            .extm3u()
+            .version(3)
+            .target_duration(10)
+            .extinf("https://media.example.com/first.ts", -5.005, None) // Invalid duration
+            .extinf("https://media.example.com/second.ts", 5.005, None)
+            .extinf("https://media.example.com/third.ts", 3.003, None)
+            .end_list()
+            .build();
+
+        assert_eq!(
+            playlist,
+            Err(vec![ValidationError::InvalidDuration(-5.005)])
+        );
+    }
+
+    #[test]
+    fn test_validate_playlist_invalid_target_duration() {
+        let playlist = PlaylistBuilder::new()
+            .extm3u()
+            .version(3)
+            .target_duration(0) // Invalid target duration
+            .extinf("https://media.example.com/first.ts", 5.005, None)
+            .extinf("https://media.example.com/second.ts", 5.005, None)
+            .extinf("https://media.example.com/third.ts", 3.003, None)
+            .end_list()
+            .build();
+
+        assert_eq!(
+            playlist,
+            Err(vec![ValidationError::InvalidTargetDuration(0)])
+        );
+    }
+
+    #[test]
+    fn test_validate_playlist_invalid_key_method() {
+        let playlist = PlaylistBuilder::new()
+            .extm3u()
+            .version(3)
+            .target_duration(10)
+            .key(
+                KeyMethod::Other("INVALID-METHOD".to_string()), // Invalid key method
+                Some("https://priv.example.com/key.php?r=52"),
+                None,
+                None,
+                None,
+            )
+            .extinf("https://media.example.com/first.ts", 5.005, None)
+            .extinf("https://media.example.com/second.ts", 5.005, None)
+            .extinf("https://media.example.com/third.ts", 3.003, None)
+            .end_list()
+            .build();
+
+        assert_eq!(
+            playlist,
+            Err(vec![ValidationError::InvalidKeyMethod(
+                "INVALID-METHOD".to_string()
+            )])
+        );
+    }
+
+    #[test]
+    fn test_validate_playlist_invalid_map_uri() {
+        let playlist = PlaylistBuilder::new()
+            .extm3u()
+            .version(6)
+            .target_duration(10)
+            .map("", None) // Invalid map URI
+            .extinf("https://media.example.com/first.ts", 5.005, None)
+            .extinf("https://media.example.com/second.ts", 5.005, None)
+            .extinf("https://media.example.com/third.ts", 3.003, None)
+            .end_list()
+            .build();
+
+        assert_eq!(playlist, Err(vec![ValidationError::InvalidMapUri]));
+    }
+
+    #[test]
+    fn test_parse_playlist_invalid_program_date_time() {
+        for date_time in [
+            "",
+            "2020-01-01",
+            "2020-02-30T00:00:00Z",
+            "2020-01-01T24:00:00Z",
+            "2020-01-01T00:00:00",
+            "2020-01-01T00:00:00.Z",
+            "2020-01-01T00:00:00+1:00",
+        ] {
+            let data = format!(
+                "#EXTM3U\n#EXT-X-TARGETDURATION:10\n#EXT-X-PROGRAM-DATE-TIME:{}\n#EXTINF:9,\na.ts\n",
+                date_time
+            );
+            assert!(
+                matches!(
+                    Playlist::from_reader(data.as_bytes()),
+                    Err(ParseError::Syntax {
+                        line: 3,
+                        kind: ParseErrorKind::InvalidTagValue(_),
+                        ..
+                    })
+                ),
+                "{}",
+                date_time
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_playlist_invalid_daterange() {
+        let playlist = PlaylistBuilder::new()
+            .extm3u()
+            .version(3)
+            .target_duration(10)
+            .date_range(
+                "",
+                None,
+                date_time("2020-01-01T00:00:00Z"),
+                Some(date_time("2020-01-01T00:00:00Z")),
+                None,
+                Some(-1.0),
+                None,
+                None,
+                vec![],
+            )
+            .date_range(
+                "ad-2",
+                None,
+                date_time("2020-01-01T00:00:10Z"),
+                Some(date_time("2020-01-01T00:00:05Z")),
+                None,
+                None,
+                None,
+                None,
+                vec![],
+            )
+            .date_range(
+                "ad-3",
+                None,
+                date_time("2020-01-01T00:00:10Z"),
+                None,
+                None,
+                None,
+                Some(true),
+                None,
+                vec![],
+            )
+            .extinf("https://media.example.com/first.ts", 5.005, None)
+            .end_list()
+            .build();
+
+        assert_eq!(
+            playlist,
+            Err(vec![
+                ValidationError::InvalidDateRangeId,
+                ValidationError::InvalidDateRangePlannedDuration(-1.0),
+                ValidationError::InvalidDateRangeEndDate,
+                ValidationError::InvalidDateRangeEndDate,
+            ])
+        );
+
+        // Dates are parsed with the playlist, so a malformed date is a parse error.
+        for attributes in [
+            r#"ID="a",START-DATE="yesterday""#,
+            r#"ID="a",START-DATE="2020-01-01T00:00:00Z",END-DATE="2020-01-01""#,
+            r#"ID="a""#,
+        ] {
+            let data = format!("#EXTM3U\n#EXT-X-DATERANGE:{}\n", attributes);
+            assert!(
+                Playlist::from_reader(data.as_bytes()).is_err(),
+                "{}",
+                attributes
+            );
+        }
+    }
+
+    #[test]
+    fn test_playlist_kind() {
+        let media = Playlist::from_reader(
+            "#EXTM3U\n#EXT-X-TARGETDURATION:10\n#EXTINF:9.0,\na.ts\n".as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(media.kind(), PlaylistKind::Media);
+
+        let master = Playlist::from_reader(
+            "#EXTM3U\n#EXT-X-VERSION:3\n#EXT-X-STREAM-INF:BANDWIDTH=1000\nlow.m3u8\n".as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(master.kind(), PlaylistKind::Master);
+
+        let empty = Playlist::from_reader("#EXTM3U\n#EXT-X-VERSION:3\n".as_bytes()).unwrap();
+        assert_eq!(empty.kind(), PlaylistKind::Ambiguous);
+    }
+
+    #[test]
+    fn test_validate_playlist_mixed_tags() {
+        let data = r#"#EXTM3U
+#EXT-X-TARGETDURATION:10
+#EXT-X-STREAM-INF:BANDWIDTH=1000
+low.m3u8
+#EXTINF:9,
+a.ts
+#EXTINF:9,
+b.ts
+"#;
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+
+        assert_eq!(playlist.kind(), PlaylistKind::Ambiguous);
+        assert_eq!(
+            playlist.validate(),
+            Err(vec![ValidationError::MixedPlaylist {
+                master_tags: vec!["EXT-X-STREAM-INF".to_string()],
+                media_tags: vec!["EXT-X-TARGETDURATION".to_string(), "EXTINF".to_string()],
+            }])
+        );
+    }
+
+    #[test]
+    fn test_required_version() {
+        let version = |data: &str| {
+            Playlist::from_reader(data.as_bytes())
+                .unwrap()
+                .required_version()
+        };
+
+        assert_eq!(version("#EXTM3U\n#EXTINF:9,\na.ts\n"), 1);
+        assert_eq!(
+            version("#EXTM3U\n#EXT-X-KEY:METHOD=AES-128,URI=\"k\",IV=0x00000000000000000000000000001234\n#EXTINF:9,\na.ts\n"),
+            2
+        );
+        assert_eq!(version("#EXTM3U\n#EXTINF:9.5,\na.ts\n"), 3);
+        assert_eq!(
+            version("#EXTM3U\n#EXT-X-BYTERANGE:100@0\n#EXTINF:9,\na.ts\n"),
+            4
+        );
+        assert_eq!(
+            version("#EXTM3U\n#EXT-X-KEY:METHOD=SAMPLE-AES,URI=\"k\",KEYFORMAT=\"identity\"\n"),
+            5
+        );
+        assert_eq!(
+            version("#EXTM3U\n#EXT-X-I-FRAMES-ONLY\n#EXT-X-MAP:URI=\"init.mp4\"\n"),
+            5
+        );
+        assert_eq!(version("#EXTM3U\n#EXT-X-MAP:URI=\"init.mp4\"\n"), 6);
+        assert_eq!(
+            version("#EXTM3U\n#EXT-X-MEDIA:TYPE=CLOSED-CAPTIONS,GROUP-ID=\"cc\",NAME=\"cc\",INSTREAM-ID=\"SERVICE3\"\n"),
+            7
+        );
+    }
+
+    #[test]
+    fn test_validate_playlist_version_too_low() {
+        let builder = PlaylistBuilder::new()
+            .extm3u()
+            .version(3)
+            .target_duration(10)
+            .key(
+                KeyMethod::SampleAes,
+                Some("key.bin"),
+                None,
+                Some("identity"),
+                None,
+            )
+            .map("init.mp4", None)
+            .extinf("https://media.example.com/first.ts", 5.005, None)
+            .end_list();
+
+        assert_eq!(
+            builder.clone().build(),
+            Err(vec![ValidationError::VersionTooLow {
+                declared: 3,
+                required: 6,
+            }])
+        );
+
+        let playlist = builder.auto_version().build().unwrap();
+        assert_eq!(playlist.tags[1], Tag::ExtXVersion(6));
+
+        let playlist = PlaylistBuilder::new()
+            .extm3u()
+            .target_duration(10)
+            .extinf("https://media.example.com/first.ts", 5.005, None)
+            .auto_version()
+            .build()
+            .unwrap();
+        assert_eq!(playlist.tags[1], Tag::ExtXVersion(3));
+    }
+
+    #[test]
+    fn test_validate_playlist_structure() {
+        let data = r#"#EXT-X-VERSION:3
+#EXTM3U
+#EXT-X-TARGETDURATION:5
+#EXT-X-PLAYLIST-TYPE:VOD
+#EXTINF:5.49,
+a.ts
+#EXTINF:5.5,
+b.ts
+#EXT-X-MEDIA-SEQUENCE:2
+#EXT-X-TARGETDURATION:5
+"#;
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+
+        assert_eq!(
+            playlist.validate(),
+            Err(vec![
+                ValidationError::ExtM3UNotFirst { index: 1 },
+                ValidationError::SegmentDurationExceedsTarget {
+                    index: 5,
+                    duration: 5.5,
+                    target_duration: 5,
+                },
+                ValidationError::MediaSequenceAfterSegment { index: 6 },
+                ValidationError::DuplicateTag {
+                    index: 7,
+                    name: "EXT-X-TARGETDURATION".to_string(),
+                },
+                ValidationError::MissingEndList { index: 3 },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_validate_playlist_invalid_playlist_type() {
+        let playlist = PlaylistBuilder::new()
+            .extm3u()
+            .version(3)
+            .target_duration(10)
+            .playlist_type(PlaylistType::Other("LIVE".to_string()))
+            .extinf("https://media.example.com/first.ts", 5.005, None)
+            .end_list()
+            .end_list()
+            .build();
+
+        assert_eq!(
+            playlist,
+            Err(vec![
+                ValidationError::InvalidPlaylistType {
+                    index: 3,
+                    playlist_type: "LIVE".to_string(),
+                },
+                ValidationError::DuplicateTag {
+                    index: 6,
+                    name: "EXT-X-ENDLIST".to_string(),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_validate_playlist_rendition_groups() {
+        let data = r#"#EXTM3U
+#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID="aac",NAME="English",DEFAULT=YES,URI="en.m3u8"
+#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID="aac",NAME="English",DEFAULT=YES,URI="en2.m3u8"
+#EXT-X-MEDIA:TYPE=SUBTITLES,GROUP-ID="subs",NAME="English",URI="subs.m3u8"
+#EXT-X-MEDIA:TYPE=CLOSED-CAPTIONS,GROUP-ID="cc",NAME="CC1",URI="cc.m3u8"
+#EXT-X-STREAM-INF:BANDWIDTH=1280000,AUDIO="aac",VIDEO="aac",SUBTITLES="subs",CLOSED-CAPTIONS="cc"
+low.m3u8
+#EXT-X-STREAM-INF:BANDWIDTH=2560000,AUDIO="ac3",CLOSED-CAPTIONS=NONE
+high.m3u8
+"#;
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+
+        assert_eq!(
+            playlist.validate(),
+            Err(vec![
+                ValidationError::MultipleDefaultRenditions {
+                    index: 2,
+                    group_id: "aac".to_string(),
+                },
+                ValidationError::DuplicateRenditionName {
+                    index: 2,
+                    group_id: "aac".to_string(),
+                    name: "English".to_string(),
+                },
+                ValidationError::ClosedCaptionsMissingInstreamId { index: 4 },
+                ValidationError::ClosedCaptionsWithUri { index: 4 },
+                ValidationError::UnknownRenditionGroup {
+                    index: 5,
+                    type_: "VIDEO".to_string(),
+                    group_id: "aac".to_string(),
+                },
+                ValidationError::UnknownRenditionGroup {
+                    index: 6,
+                    type_: "AUDIO".to_string(),
+                    group_id: "ac3".to_string(),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_validate_playlist_low_latency() {
+        // The Low-Latency HLS rules are errors under RFC 8216bis only.
+        let rfc8216bis = Validator::new(Profile::Rfc8216bis);
+        let data = r#"#EXTM3U
+#EXT-X-TARGETDURATION:4
+#EXT-X-SERVER-CONTROL:CAN-BLOCK-RELOAD=YES,HOLD-BACK=8.0,PART-HOLD-BACK=1.0,CAN-SKIP-UNTIL=12.0
+#EXT-X-PART-INF:PART-TARGET=1.0
+#EXT-X-SKIP:SKIPPED-SEGMENTS=3
+#EXT-X-PART:DURATION=1.0,URI="part1.mp4"
+#EXT-X-PART:DURATION=1.5,URI="part2.mp4"
+#EXTINF:3,
+segment.mp4
+#EXT-X-PRELOAD-HINT:TYPE=PART,URI="part3.mp4"
+#EXT-X-PART:DURATION=1.0,URI="part3.mp4"
+#EXT-X-PRELOAD-HINT:TYPE=PART,URI="part4.mp4"
+#EXT-X-RENDITION-REPORT:URI="other.m3u8",BANDWIDTH=1000
+"#;
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+
+        assert_eq!(
+            playlist.validate_with(&rfc8216bis),
+            Err(vec![
+                ValidationError::HoldBackTooShort {
+                    index: 2,
+                    hold_back: 8.0,
+                    target_duration: 4,
+                },
+                ValidationError::PartHoldBackTooShort {
+                    index: 2,
+                    part_hold_back: 1.0,
+                    part_target: 1.0,
+                },
+                ValidationError::CanSkipUntilTooShort {
+                    index: 2,
+                    can_skip_until: 12.0,
+                    target_duration: 4,
+                },
+                ValidationError::PartDurationExceedsTarget {
+                    index: 6,
+                    duration: 1.5,
+                    part_target: 1.0,
+                },
+                ValidationError::PreloadHintNotAtTail { index: 8 },
+            ])
+        );
+
+        let data = r#"#EXTM3U
+#EXT-X-TARGETDURATION:4
+#EXT-X-SKIP:SKIPPED-SEGMENTS=3
+#EXT-X-PART:DURATION=1.0,URI="part1.mp4"
+#EXTINF:3,
+segment.mp4
+"#;
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+
+        assert_eq!(
+            playlist.validate_with(&rfc8216bis),
+            Err(vec![
+                ValidationError::MissingPartInf { index: 3 },
+                ValidationError::SkipWithoutCanSkipUntil { index: 2 },
+            ])
+        );
+
+        let data = r#"#EXTM3U
+#EXT-X-TARGETDURATION:4
+#EXT-X-SERVER-CONTROL:CAN-BLOCK-RELOAD=YES,HOLD-BACK=12.0,CAN-SKIP-DATERANGES=YES
+#EXTINF:3,
+segment.mp4
+"#;
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+
+        assert_eq!(
+            playlist.tags[2],
+            Tag::ExtXServerControl {
+                can_play: None,
+                can_seek: None,
+                can_pause: None,
+                min_buffer_time: None,
+                can_block_reload: Some(true),
+                hold_back: Some(12.0),
+                part_hold_back: None,
+                can_skip_until: None,
+                can_skip_dateranges: Some(true),
+                other: Vec::new(),
+            }
+        );
+        assert_eq!(
+            playlist.to_string(),
+            data.replace("HOLD-BACK=12.0", "HOLD-BACK=12")
+        );
+        assert_eq!(
+            playlist.validate_with(&rfc8216bis),
+            Err(vec![ValidationError::InvalidServerControl])
+        );
+    }
+
+    #[test]
+    fn test_validator_profiles() {
+        let rules = |playlist: &Playlist, validator: Validator| -> Vec<(String, Severity)> {
+            validator
+                .validate(playlist)
+                .into_iter()
+                .map(|diagnostic| (diagnostic.rule_id, diagnostic.severity))
+                .collect()
+        };
+        let rule = |rule_id: &str, severity: Severity| (rule_id.to_string(), severity);
+
+        let data = r#"#EXTM3U
+#EXT-X-TARGETDURATION:4
+#EXT-X-PLAYLIST-TYPE:VOD
+#EXT-X-SERVER-CONTROL:CAN-SKIP-UNTIL=12.0
+#EXTINF:4,
+a.ts
+#EXT-X-GAP
+#EXTINF:4,
+b.ts
+"#;
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+
+        // RFC 8216 does not define the Low-Latency HLS tags, so it only reports them.
+        assert_eq!(
+            rules(&playlist, Validator::new(Profile::Rfc8216)),
+            vec![
+                rule("rfc8216bis-tag", Severity::Warning),
+                rule("rfc8216bis-tag", Severity::Warning),
+                rule("vod-endlist", Severity::Error),
+                rule("can-skip-until", Severity::Info),
+            ]
+        );
+        assert_eq!(
+            rules(&playlist, Validator::new(Profile::Rfc8216bis)),
+            vec![
+                rule("vod-endlist", Severity::Error),
+                rule("can-skip-until", Severity::Error),
+            ]
+        );
+        assert_eq!(
+            rules(&playlist, Validator::new(Profile::AppleHls)),
+            vec![
+                rule("vod-endlist", Severity::Error),
+                rule("can-skip-until", Severity::Error),
+            ]
+        );
+        assert_eq!(
+            rules(&playlist, Validator::new(Profile::Lenient)),
+            vec![
+                rule("vod-endlist", Severity::Warning),
+                rule("can-skip-until", Severity::Warning),
+            ]
+        );
+        assert_eq!(
+            rules(
+                &playlist,
+                Validator::new(Profile::Rfc8216bis)
+                    .disable("can-skip-until")
+                    .severity("rfc8216bis-tag", Severity::Info)
+            ),
+            vec![
+                rule("rfc8216bis-tag", Severity::Info),
+                rule("rfc8216bis-tag", Severity::Info),
+                rule("vod-endlist", Severity::Error),
+            ]
+        );
+
+        // `validate` only fails on errors of the RFC 8216 profile.
+        assert_eq!(
+            playlist.validate(),
+            Err(vec![ValidationError::MissingEndList { index: 2 }])
+        );
+
+        // Only the Apple profile turns the SHOULD rules into errors.
+        let data = "#EXTM3U\n#EXT-X-STREAM-INF:BANDWIDTH=1280000\nlow.m3u8\n";
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+        for (profile, severity) in [
+            (Profile::Rfc8216, Severity::Warning),
+            (Profile::Rfc8216bis, Severity::Warning),
+            (Profile::AppleHls, Severity::Error),
+            (Profile::Lenient, Severity::Warning),
+        ] {
+            assert_eq!(
+                rules(&playlist, Validator::new(profile)),
+                vec![rule("stream-inf-codecs", severity)]
+            );
+        }
+        assert_eq!(playlist.validate(), Ok(()));
+        assert_eq!(
+            playlist.validate_with(&Validator::new(Profile::AppleHls)),
+            Err(vec![ValidationError::StreamInfMissingCodecs { index: 1 }])
+        );
+    }
+
+    #[test]
+    fn test_authoring_checker() {
+        let data = r#"#EXTM3U
+#EXT-X-INDEPENDENT-SEGMENTS
+#EXT-X-STREAM-INF:BANDWIDTH=1280000,AVERAGE-BANDWIDTH=1000000,CODECS="avc1.4d401e,mp4a.40.2",RESOLUTION=640x360,FRAME-RATE=30.000
+low.m3u8
+#EXT-X-STREAM-INF:BANDWIDTH=2560000,CODECS="avc1.64001f,mp4a.40.2",RESOLUTION=1280x720
+high.m3u8
+#EXT-X-STREAM-INF:BANDWIDTH=64000,CODECS="mp4a.40.2"
+audio.m3u8
+#EXT-X-I-FRAME-STREAM-INF:BANDWIDTH=86000,RESOLUTION=640x360,URI="low-iframe.m3u8"
+"#;
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+        let report = Checker::new().check(&playlist);
+
+        assert_eq!(
+            report
+                .results
+                .iter()
+                .map(|result| result.id)
+                .collect::<Vec<_>>(),
+            Checker::rule_ids().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            report.failed().map(|result| result.id).collect::<Vec<_>>(),
+            vec![
+                "apple-average-bandwidth",
+                "apple-frame-rate",
+                "apple-iframe-variants",
+            ]
+        );
+        assert_eq!(
+            report.result("apple-average-bandwidth").unwrap().failures,
+            vec![
+                Failure {
+                    index: Some(3),
+                    message: "variant high.m3u8 has no AVERAGE-BANDWIDTH".to_string(),
+                },
+                Failure {
+                    index: Some(4),
+                    message: "variant audio.m3u8 has no AVERAGE-BANDWIDTH".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            report.result("apple-iframe-variants").unwrap().failures[0].index,
+            Some(3)
+        );
+        assert!(report
+            .to_string()
+            .starts_with("PASS apple-independent-segments: "));
+
+        let data = r#"#EXTM3U
+#EXT-X-TARGETDURATION:10
+#EXT-X-BYTERANGE:1500000@0
+#EXTINF:10,
+media.ts
+#EXT-X-BYTERANGE:1000000
+#EXTINF:10,
+media.ts
+"#;
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+        let report = Checker::new().declared_bandwidth(1000000).check(&playlist);
+
+        assert_eq!(
+            report.failed().map(|result| result.id).collect::<Vec<_>>(),
+            vec![
+                "apple-independent-segments",
+                "apple-target-duration",
+                "apple-segment-bitrate",
+            ]
+        );
+        assert_eq!(
+            report.result("apple-segment-bitrate").unwrap().failures[0].index,
+            Some(3)
+        );
+
+        // Without a declared bandwidth, or without bit rates, the rule is not evaluated.
+        let report = Checker::new().check(&playlist);
+        let result = report.result("apple-segment-bitrate").unwrap();
+        assert_eq!(result.skipped, Some("no declared BANDWIDTH"));
+        assert!(result.passed());
+        assert!(report.to_string().contains("SKIP apple-segment-bitrate: "));
+
+        let data = "#EXTM3U\n#EXT-X-TARGETDURATION:6\n#EXTINF:6,\na.ts\n";
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+        let report = Checker::new().declared_bandwidth(1000000).check(&playlist);
+        assert!(report.result("apple-segment-bitrate").unwrap().is_skipped());
+
+        // A peak far below the declared bandwidth fails as well.
+        let data = "#EXTM3U\n#EXT-X-TARGETDURATION:6\n#EXT-X-BITRATE:500\n#EXTINF:6,\na.ts\n";
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+        let report = Checker::new().declared_bandwidth(1000000).check(&playlist);
+        let result = report.result("apple-segment-bitrate").unwrap();
+        assert!(!result.is_skipped());
+        assert_eq!(
+            result.failures,
+            vec![Failure {
+                index: None,
+                message: "peak segment bit rate is 500000 bit/s, declared BANDWIDTH is 1000000"
+                    .to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_validator_custom_rules() {
+        struct MaxBandwidth(u32);
+
+        impl ValidationRule for MaxBandwidth {
+            fn id(&self) -> &'static str {
+                "max-bandwidth"
+            }
+
+            fn severity(&self) -> Severity {
+                Severity::Warning
+            }
+
+            fn check(&self, playlist: &Playlist) -> Vec<RuleViolation> {
+                playlist
+                    .tags
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(index, tag)| match tag {
+                        Tag::ExtXStreamInf { bandwidth, uri, .. } if *bandwidth > self.0 => {
+                            Some(RuleViolation {
+                                index: Some(index),
+                                message: format!("{} exceeds {} bit/s", uri, self.0),
+                            })
+                        }
+                        _ => None,
+                    })
+                    .collect()
+            }
+        }
+
+        let data = r#"#EXTM3U
+#EXT-X-STREAM-INF:BANDWIDTH=1280000,CODECS="avc1.4d401e"
+low.m3u8
+#EXT-X-STREAM-INF:BANDWIDTH=9000000,CODECS="hvc1.2.4.L150"
+uhd.m3u8
+"#;
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+        let validator = Validator::new(Profile::Rfc8216).rule(MaxBandwidth(8000000));
+
+        assert_eq!(
+            validator.validate(&playlist),
+            vec![Diagnostic {
+                rule_id: "max-bandwidth".to_string(),
+                severity: Severity::Warning,
+                error: ValidationError::Custom {
+                    rule_id: "max-bandwidth".to_string(),
+                    index: Some(2),
+                    message: "uhd.m3u8 exceeds 8000000 bit/s".to_string(),
+                },
+            }]
+        );
+        assert_eq!(playlist.validate_with(&validator), Ok(()));
+
+        let validator = validator.severity("max-bandwidth", Severity::Error);
+        assert_eq!(
+            playlist.validate_with(&validator).unwrap_err()[0].rule_id(),
+            "max-bandwidth"
+        );
+        assert!(validator
+            .disable("max-bandwidth")
+            .validate(&playlist)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_typed_values() {
+        let range: ByteRange = "1000@200".parse().unwrap();
+        assert_eq!(
+            range,
+            ByteRange {
+                length: 1000,
+                offset: Some(200)
+            }
+        );
+        assert_eq!(range.to_string(), "1000@200");
+        assert_eq!("75232".parse::<ByteRange>().unwrap().offset, None);
+        assert!("1000@".parse::<ByteRange>().is_err());
+        assert!("-1".parse::<ByteRange>().is_err());
+
+        let resolution: Resolution = "1920x1080".parse().unwrap();
+        assert_eq!(resolution.width, 1920);
+        assert_eq!(resolution.to_string(), "1920x1080");
+        assert!("1920X1080".parse::<Resolution>().is_err());
+
+        let iv: InitializationVector = "0X0000000000000000000000000000ABCD".parse().unwrap();
+        assert_eq!(iv, InitializationVector::new(0xabcd));
+        assert_eq!(iv.to_bytes()[14..], [0xab, 0xcd]);
+        assert_eq!(iv.to_string(), "0X0000000000000000000000000000ABCD");
+        let iv: InitializationVector = "0x1234567890ABCDEF".parse().unwrap();
+        assert_eq!(iv, InitializationVector::new(0x1234567890abcdef));
+        assert_eq!(iv.digits(), 16);
+        assert_eq!(iv.to_string(), "0x1234567890ABCDEF");
+        assert_eq!(
+            InitializationVector::new(0xabcd).to_string(),
+            "0x0000000000000000000000000000abcd"
+        );
+        assert!("0x".parse::<InitializationVector>().is_err());
+        assert!("0x9c7db8778570d05c0123456789abcdef0"
+            .parse::<InitializationVector>()
+            .is_err());
+
+        assert_eq!("-12.5".parse::<TimeOffset>(), Ok(TimeOffset(-12.5)));
+        assert_eq!("10".parse::<TimeOffset>(), Ok(TimeOffset(10.0)));
+        assert!("+1".parse::<TimeOffset>().is_err());
+        assert!("1e3".parse::<TimeOffset>().is_err());
+
+        assert_eq!("SAMPLE-AES-CTR".parse(), Ok(KeyMethod::SampleAesCtr));
+        assert_eq!("CLOSED-CAPTIONS".parse(), Ok(MediaType::ClosedCaptions));
+        assert_eq!("VOD".parse(), Ok(PlaylistType::Vod));
+        assert!("LIVE".parse::<PlaylistType>().is_err());
+    }
+
+    #[test]
+    fn test_parse_playlist_typed_values() {
+        let data = r#"#EXTM3U
+#EXT-X-VERSION:4
+#EXT-X-TARGETDURATION:10
+#EXT-X-PLAYLIST-TYPE:LIVE
+#EXT-X-KEY:METHOD=AES-256,URI="key.bin"
+#EXT-X-BYTERANGE:1000@0
+#EXTINF:9,
+a.ts
+"#;
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+        assert_eq!(
+            playlist.tags[3],
+            Tag::ExtXPlaylistType(PlaylistType::Other("LIVE".to_string()))
+        );
+        assert_eq!(
+            playlist.tags[5],
+            Tag::ExtXByteRange(ByteRange {
+                length: 1000,
+                offset: Some(0)
+            })
+        );
+        assert_eq!(
+            playlist.validate(),
+            Err(vec![
+                ValidationError::InvalidKeyMethod("AES-256".to_string()),
+                ValidationError::InvalidPlaylistType {
+                    index: 3,
+                    playlist_type: "LIVE".to_string()
+                },
+            ])
+        );
+
+        let master = "#EXTM3U\n#EXT-X-MEDIA:TYPE=CAPTIONS,GROUP-ID=\"cc\",NAME=\"en\"\n";
+        let playlist = Playlist::from_reader(master.as_bytes()).unwrap();
+        assert_eq!(
+            playlist.validate(),
+            Err(vec![ValidationError::InvalidMediaType {
+                index: 1,
+                media_type: "CAPTIONS".to_string()
+            }])
+        );
+
+        for line in [
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"k\",IV=0x9c7db8778570d05c0123456789abcdef0",
+            "#EXT-X-MAP:URI=\"init.mp4\",BYTERANGE=\"800@\"",
+            "#EXT-X-BYTERANGE:800@-1",
+        ] {
+            let data = format!("#EXTM3U\n{}\n#EXTINF:9,\na.ts\n", line);
+            assert!(Playlist::from_reader(data.as_bytes()).is_err(), "{}", line);
+        }
+
+        let data = "#EXTM3U\n#EXT-X-VERSION:2\n#EXT-X-TARGETDURATION:10\n\
+                    #EXT-X-KEY:METHOD=AES-128,URI=\"k\",IV=0x1234567890ABCDEF\n#EXTINF:9,\na.ts\n";
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+        assert_eq!(playlist.to_string(), data);
+        assert_eq!(
+            playlist.validate(),
+            Err(vec![ValidationError::InvalidInitializationVector(
+                "0x1234567890ABCDEF".to_string()
+            )])
+        );
+    }
+
+    #[test]
+    fn test_decimal_durations() {
+        let data = "#EXTM3U\n#EXT-X-VERSION:3\n#EXT-X-TARGETDURATION:10\n#EXTINF:5.0050,\na.ts\n#EXTINF:0.1,\nb.ts\n#EXTINF:0.2,\nc.ts\n#EXTINF:10,\nd.ts\n";
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+        assert_eq!(
+            playlist.tags[3],
+            Tag::ExtInf("a.ts".to_string(), decimal("5.005"), None)
+        );
+
+        let mut output = Vec::new();
+        for tag in &playlist.tags {
+            writeln!(output, "{}", tag).unwrap();
+        }
+        assert_eq!(String::from_utf8(output).unwrap(), data);
+
+        let media = MediaPlaylist::from(&playlist);
+        assert_eq!(media.duration().unwrap().to_string(), "15.3050");
+        assert_eq!(
+            media.segments[1]
+                .duration
+                .checked_add(media.segments[2].duration),
+            Some(decimal("0.3"))
+        );
+
+        assert_eq!(decimal("-0.05").to_string(), "-0.05");
+        assert_eq!(decimal("2.5").round(0), Some(decimal("3")));
+        assert_eq!(decimal("-2.5").round(0), Some(decimal("-3")));
+        assert_eq!(decimal("6").round(3).unwrap().to_string(), "6.000");
+        assert_eq!(decimal("10").round(18), None);
+        assert_eq!(decimal("6").round(19), None);
+        assert!(decimal("6.00").is_integer());
+        assert_eq!(Decimal::from_f32(5.005), Some(decimal("5.005")));
+        assert_eq!(Decimal::from_f32(f32::NAN), None);
+        assert!("5.".parse::<Decimal>().is_err());
+        assert!(".5".parse::<Decimal>().is_err());
+        for duration in ["5.", ".5", "-1", "1e3", "5.0.1", ""] {
+            let data = format!("#EXTM3U\n#EXTINF:{},\na.ts\n", duration);
+            assert!(
+                Playlist::from_reader(data.as_bytes()).is_err(),
+                "{}",
+                duration
+            );
+        }
+
+        let playlist = PlaylistBuilder::new()
+            .extm3u()
+            .version(3)
+            .target_duration(10)
+            .extinf("a.ts", 6.0, None)
+            .extinf("b.ts", 5.0049, None)
+            .precision(Precision::Fixed(3))
+            .build()
+            .unwrap();
+        assert_eq!(playlist.tags[3].to_string(), "#EXTINF:6.000,\na.ts");
+        assert_eq!(playlist.tags[4].to_string(), "#EXTINF:5.005,\nb.ts");
+
+        let playlist = PlaylistBuilder::new()
+            .extm3u()
+            .target_duration(10)
+            .extinf("a.ts", 5.6, None)
+            .precision(Precision::Fixed(0))
+            .auto_version()
+            .build()
+            .unwrap();
+        assert_eq!(playlist.tags[1], Tag::ExtXVersion(1));
+        assert_eq!(playlist.tags[3].to_string(), "#EXTINF:6,\na.ts");
+    }
+
+    #[test]
+    fn test_decimal_overflow_does_not_panic() {
+        let data = "#EXTM3U\n#EXT-X-TARGETDURATION:18446744073709551615\n#EXTINF:10,\na.ts\n";
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+        assert_eq!(playlist.validate(), Ok(()));
+
+        let data = "#EXTM3U\n#EXT-X-TARGETDURATION:10\n#EXTINF:10,\na.ts\n";
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+        let options = SerializeOptions {
+            precision: Precision::Fixed(18),
+            ..SerializeOptions::default()
+        };
+        assert!(playlist
+            .to_string_with(&options)
+            .contains("#EXTINF:10.00000000000000000,"));
+
+        let mut data = String::from("#EXTM3U\n#EXT-X-VERSION:3\n#EXT-X-TARGETDURATION:2\n");
+        for index in 0..10 {
+            data.push_str(&format!("#EXTINF:1.000000000000000001,\n{}.ts\n", index));
+        }
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+        assert_eq!(MediaPlaylist::from(&playlist).duration(), None);
+    }
+
+    #[test]
+    fn test_date_time() {
+        let date_time: DateTime = "2024-11-05T12:00:00.5+01:00".parse().unwrap();
+        assert_eq!(date_time.offset(), 60);
+        assert_eq!(date_time.unix_nanos(), 1_730_804_400_500_000_000);
+        assert_eq!(date_time.to_string(), "2024-11-05T12:00:00.5+01:00");
+        assert_eq!(
+            date_time,
+            "2024-11-05T11:00:00.500Z".parse::<DateTime>().unwrap()
+        );
+        assert_eq!(
+            "2024-11-05T10:30:00.5-0030".parse::<DateTime>().unwrap(),
+            date_time
+        );
+        assert!(
+            "2024-02-29T23:59:59.999999999Z"
+                .parse::<DateTime>()
+                .unwrap()
+                < "2024-03-01T00:00:00Z".parse().unwrap()
+        );
+        assert!("2023-02-29T00:00:00Z".parse::<DateTime>().is_err());
+
+        let later = date_time.add_seconds(decimal("43200.25"));
+        assert_eq!(later.to_string(), "2024-11-06T00:00:00.75+01:00");
+        let earlier = "1970-01-01T00:00:00.000Z"
+            .parse::<DateTime>()
+            .unwrap()
+            .add_seconds(decimal("-0.001"));
+        assert_eq!(earlier.to_string(), "1969-12-31T23:59:59.999Z");
+        assert_eq!(
+            DateTime::from_unix_nanos(0, -90, 0).to_string(),
+            "1969-12-31T22:30:00-01:30"
+        );
+    }
+
+    #[test]
+    fn test_media_playlist_timeline() {
+        let data = r#"#EXTM3U
+#EXT-X-TARGETDURATION:10
+#EXTINF:4,
+a.ts
+#EXT-X-PROGRAM-DATE-TIME:2020-01-01T00:00:00.000Z
+#EXTINF:9.009,
+b.ts
+#EXTINF:9.009,
+c.ts
+#EXT-X-PROGRAM-DATE-TIME:2020-01-01T00:00:20.000Z
+#EXTINF:9.009,
+d.ts
+#EXT-X-PROGRAM-DATE-TIME:2020-01-01T01:00:29.000+01:00
+#EXTINF:9.009,
+e.ts
+#EXT-X-DISCONTINUITY
+#EXT-X-PROGRAM-DATE-TIME:2020-01-02T00:00:00Z
+#EXTINF:9.009,
+f.ts
+"#;
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+        let timeline = MediaPlaylist::from(&playlist).timeline(Duration::from_millis(1));
+
+        let start_times: Vec<_> = timeline
+            .start_times
+            .iter()
+            .map(|time| time.as_ref().map(DateTime::to_string))
+            .collect();
+        assert_eq!(
+            start_times,
+            vec![
+                None,
+                Some("2020-01-01T00:00:00.000Z".to_string()),
+                Some("2020-01-01T00:00:09.009Z".to_string()),
+                Some("2020-01-01T00:00:20.000Z".to_string()),
+                Some("2020-01-01T01:00:29.000+01:00".to_string()),
+                Some("2020-01-02T00:00:00Z".to_string()),
+            ]
+        );
+
+        assert_eq!(timeline.mismatches.len(), 2);
+        let gap = &timeline.mismatches[0];
+        assert_eq!(gap.segment, 3);
+        assert_eq!(gap.expected.to_string(), "2020-01-01T00:00:18.018Z");
+        assert!(gap.is_gap());
+        let overlap = &timeline.mismatches[1];
+        assert_eq!(overlap.segment, 4);
+        assert!(overlap.is_overlap());
+
+        let lenient = MediaPlaylist::from(&playlist).timeline(Duration::from_secs(2));
+        assert_eq!(lenient.mismatches.len(), 0);
+    }
+
+    #[test]
+    fn test_serialize_options() {
+        let data = r#"#EXTM3U
+#EXT-X-VERSION:3
+#EXT-X-TARGETDURATION:10
+#EXT-X-KEY:METHOD=AES-128,URI="key.bin"
+#EXTINF:9.0090,
+a.ts
+#EXTINF:9,
+b.ts
+#EXT-X-ENDLIST
+"#;
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+        assert_eq!(playlist.to_string(), data);
+
+        let mut output = Vec::new();
+        playlist.write_to(&mut output).unwrap();
+        assert_eq!(output, data.as_bytes());
+
+        let options = SerializeOptions {
+            line_ending: LineEnding::CrLf,
+            precision: Precision::Fixed(3),
+            attribute_order: AttributeOrder::Canonical,
+            empty_title_comma: false,
+        };
+        assert_eq!(
+            playlist.to_string_with(&options),
+            "#EXTM3U\r\n#EXT-X-VERSION:3\r\n#EXT-X-TARGETDURATION:10\r\n\
+             #EXT-X-KEY:METHOD=AES-128,URI=\"key.bin\"\r\n#EXTINF:9.009\r\na.ts\r\n\
+             #EXTINF:9.000\r\nb.ts\r\n#EXT-X-ENDLIST\r\n"
+        );
+
+        let master = PlaylistBuilder::new()
+            .extm3u()
+            .media(
+                MediaType::Audio,
+                "aac",
+                Some("English"),
+                None,
+                Some(true),
+                None,
+                None,
+                Some("en"),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .build()
+            .unwrap();
+        let options = SerializeOptions {
+            attribute_order: AttributeOrder::Canonical,
+            ..SerializeOptions::default()
+        };
+        assert_eq!(
+            master.to_string_with(&options),
+            "#EXTM3U\n#EXT-X-MEDIA:DEFAULT=YES,GROUP-ID=\"aac\",LANGUAGE=\"en\",NAME=\"English\",TYPE=AUDIO\n"
+        );
+
+        // Floating-point attributes are written in their shortest form.
+        let data = "#EXTM3U\n#EXT-X-START:TIME-OFFSET=0.0\n#EXT-X-STREAM-INF:BANDWIDTH=1280000,FRAME-RATE=29.970\nlow.m3u8\n";
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+        assert_eq!(
+            playlist.to_string(),
+            "#EXTM3U\n#EXT-X-START:TIME-OFFSET=0\n#EXT-X-STREAM-INF:BANDWIDTH=1280000,FRAME-RATE=29.97\nlow.m3u8\n"
+        );
+
+        // The source order of attributes is not kept.
+        let data = "#EXTM3U\n#EXT-X-MEDIA:NAME=\"English\",TYPE=AUDIO,GROUP-ID=\"aac\"\n";
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+        assert_eq!(
+            playlist.to_string_with(&SerializeOptions {
+                attribute_order: AttributeOrder::Display,
+                ..SerializeOptions::default()
+            }),
+            "#EXTM3U\n#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"aac\",NAME=\"English\"\n"
+        );
+    }
+
+    #[test]
+    fn test_tag_display_quoting() {
+        let data = r#"#EXTM3U
+#EXT-X-VERSION:6
+#EXT-X-SESSION-KEY:METHOD=SAMPLE-AES,URI="skd://key",IV=0x0123456789abcdef0123456789abcdef
+#EXT-X-TARGETDURATION:10
+#EXT-X-KEY:METHOD=SAMPLE-AES,URI="skd://key",KEYFORMAT="com.apple.streamingkeydelivery",KEYFORMATVERSIONS="1"
+#EXT-X-MAP:URI="init.mp4",BYTERANGE="800@0"
+#EXTINF:10,Title
+first.ts
+#EXT-X-PART:URI="part.ts",DURATION=1,INDEPENDENT=NO
+#EXT-X-PRELOAD-HINT:TYPE=PART,URI="next.ts",BYTERANGE="1000@2000"
+#EXT-X-DATERANGE:ID="ad",START-DATE="2024-11-05T12:00:00Z",X-COUNT=2,X-RATIO=2.0,X-DELTA=-1.5
+"#;
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+        assert_eq!(playlist.to_string(), data);
+        assert_eq!(
+            Playlist::from_reader(playlist.to_string().as_bytes()).unwrap(),
+            playlist
+        );
+    }
+
+    #[test]
+    fn test_builder_is_owned() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<PlaylistBuilder>();
+
+        let data =
+            "#EXTM3U\n#EXT-X-VERSION:3\n#EXT-X-TARGETDURATION:10\n#EXTINF:9.009,\nfirst.ts\n";
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+        let builder = PlaylistBuilder::from_playlist(&playlist).extinf("second.ts", 9.009, None);
+
+        // A clone is independent of the original.
+        let ended = builder.clone().end_list().build().unwrap();
+        let live = builder.extinf("third.ts", 9.009, None).build().unwrap();
+        assert_eq!(
+            ended.to_string(),
+            "#EXTM3U\n#EXT-X-VERSION:3\n#EXT-X-TARGETDURATION:10\n#EXTINF:9.009,\nfirst.ts\n\
+             #EXTINF:9.009,\nsecond.ts\n#EXT-X-ENDLIST\n"
+        );
+        assert_eq!(live.tags.len(), 6);
+        assert!(!live.tags.contains(&Tag::ExtXEndList));
+
+        let handle = std::thread::spawn(move || {
+            PlaylistBuilder::from_playlist(&live)
+                .end_list()
+                .build()
+                .unwrap()
+        });
+        assert_eq!(handle.join().unwrap().tags.len(), 7);
+    }
+
+    #[test]
+    fn test_media_playlist_builder() {
+        let key = |uri: &str| Key {
+            method: KeyMethod::Aes128,
+            uri: Some(uri.to_string()),
+            iv: None,
+            keyformat: None,
+            keyformatversions: None,
+            other: Vec::new(),
+        };
+        let map = Map {
+            uri: "init.mp4".to_string(),
+            byterange: None,
+            other: Vec::new(),
+        };
+        let start: DateTime = "2024-11-05T12:00:00Z".parse().unwrap();
+
+        let playlist = MediaPlaylistBuilder::new()
+            .media_sequence(7)
+            .playlist_type(PlaylistType::Vod)
+            .segment(
+                SegmentBuilder::new("1.mp4", decimal("6.006"))
+                    .key(key("a.key"))
+                    .map(map.clone())
+                    .program_date_time(start)
+                    .title("Intro"),
+            )
+            .segment(
+                SegmentBuilder::new("2.mp4", decimal("5.5"))
+                    .key(key("a.key"))
+                    .map(map.clone()),
+            )
+            .segment(SegmentBuilder::new("3.mp4", decimal("4")).key(key("b.key")))
+            .segment(
+                SegmentBuilder::new("4.mp4", decimal("4"))
+                    .discontinuity()
+                    .byte_range(ByteRange {
+                        length: 1000,
+                        offset: Some(0),
+                    }),
+            )
             .end_list()
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            playlist.to_string(),
+            r#"#EXTM3U
+#EXT-X-VERSION:6
+#EXT-X-TARGETDURATION:6
+#EXT-X-MEDIA-SEQUENCE:7
+#EXT-X-PLAYLIST-TYPE:VOD
+#EXT-X-KEY:METHOD=AES-128,URI="a.key"
+#EXT-X-MAP:URI="init.mp4"
+#EXT-X-PROGRAM-DATE-TIME:2024-11-05T12:00:00Z
+#EXTINF:6.006,Intro
+1.mp4
+#EXTINF:5.5,
+2.mp4
+#EXT-X-KEY:METHOD=AES-128,URI="b.key"
+#EXTINF:4,
+3.mp4
+#EXT-X-DISCONTINUITY
+#EXT-X-KEY:METHOD=NONE
+#EXT-X-BYTERANGE:1000@0
+#EXTINF:4,
+4.mp4
+#EXT-X-ENDLIST
+"#
+        );
+
+        // The typed view resolves the same keys and maps.
+        let media = MediaPlaylist::from(&playlist);
+        assert_eq!(media.segments[1].key, Some(key("a.key")));
+        assert_eq!(media.segments[2].key, Some(key("b.key")));
+        assert_eq!(media.segments[3].key, None);
+        assert_eq!(media.segments[3].map, Some(map));
+
+        let empty = MediaPlaylistBuilder::new().build().unwrap();
+        assert_eq!(empty.to_string(), "#EXTM3U\n#EXT-X-TARGETDURATION:1\n");
+
+        // A playlist that would not validate is refused.
+        let result = MediaPlaylistBuilder::new()
+            .target_duration(5)
+            .segment(SegmentBuilder::new("long.ts", decimal("9.009")))
             .build();
+        assert_eq!(
+            result.unwrap_err(),
+            vec![ValidationError::SegmentDurationExceedsTarget {
+                index: 3,
+                duration: 9.009,
+                target_duration: 5,
+            }]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_json() {
+        use serde_json::json;
+
+        let data = r#"#EXTM3U
+#EXT-X-TARGETDURATION:10
+#EXT-X-KEY:METHOD=AES-128,URI="key.bin",IV=0x000102030405060708090a0b0c0d0e0f
+#EXT-X-PROGRAM-DATE-TIME:2024-11-05T12:00:00.000+01:00
+#EXTINF:9.0090,Intro
+first.ts
+#EXT-X-DATERANGE:ID="ad",START-DATE="2024-11-05T12:00:00Z",X-AD-ID="1234"
+#EXT-X-CUE-OUT:30
+# comment
+"#;
+        let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+        let value = serde_json::to_value(&playlist).unwrap();
+        assert_eq!(
+            value,
+            json!({"tags": [
+                "EXTM3U",
+                {"EXT-X-TARGETDURATION": 10},
+                {"EXT-X-KEY": {
+                    "method": "AES-128",
+                    "uri": "key.bin",
+                    "iv": "0x000102030405060708090a0b0c0d0e0f",
+                    "keyformat": null,
+                    "keyformatversions": null,
+                }},
+                {"EXT-X-PROGRAM-DATE-TIME": "2024-11-05T12:00:00.000+01:00"},
+                {"EXTINF": {"uri": "first.ts", "duration": "9.0090", "title": "Intro"}},
+                {"EXT-X-DATERANGE": {
+                    "id": "ad",
+                    "class": null,
+                    "start-date": "2024-11-05T12:00:00Z",
+                    "end-date": null,
+                    "duration": null,
+                    "planned-duration": null,
+                    "end-on-next": null,
+                    "cue": null,
+                    "client-attributes": [["X-AD-ID", {"quoted-string": "1234"}]],
+                }},
+                {"unknown": {"name": "EXT-X-CUE-OUT", "value": "30"}},
+                {"comment": " comment"},
+            ]})
+        );
+        let parsed: Playlist = serde_json::from_value(value).unwrap();
+        assert_eq!(parsed, playlist);
+        assert_eq!(parsed.to_string(), data);
+
+        // Absent attributes may be left out, and unknown methods are kept.
+        let tag: Tag =
+            serde_json::from_str(r#"{"EXT-X-MEDIA": {"type": "AUDIO", "group-id": "aac"}}"#)
+                .unwrap();
+        assert_eq!(tag.to_string(), r#"#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID="aac""#);
+        let tag: Tag =
+            serde_json::from_str(r#"{"EXT-X-SESSION-KEY": {"method": "X-CUSTOM"}}"#).unwrap();
+        assert_eq!(
+            tag,
+            Tag::ExtXSessionKey {
+                method: KeyMethod::Other("X-CUSTOM".to_string()),
+                uri: None,
+                iv: None,
+                other: Vec::new(),
+            }
+        );
+        assert!(serde_json::from_str::<Tag>(r#"{"EXT-X-BYTERANGE": "1000@"}"#).is_err());
+
+        let media = MediaPlaylist::from(&playlist);
+        let value = serde_json::to_value(&media).unwrap();
+        assert_eq!(value["segments"][0]["duration"], json!("9.0090"));
+        assert_eq!(value["segments"][0]["key"]["method"], json!("AES-128"));
+        assert_eq!(
+            serde_json::from_value::<MediaPlaylist>(value).unwrap(),
+            media
+        );
+
+        let errors = playlist.validate().unwrap_err();
+        assert_eq!(
+            serde_json::to_value(&errors).unwrap(),
+            json!([{"version-too-low": {"declared": 1, "required": 3}}])
+        );
+        let errors: Vec<ValidationError> = serde_json::from_value(json!([
+            "missing-extm3u",
+            {"duplicate-tag": {"index": 2, "name": "EXT-X-TARGETDURATION"}},
+        ]))
+        .unwrap();
+        assert_eq!(
+            errors,
+            vec![
+                ValidationError::MissingExtM3U,
+                ValidationError::DuplicateTag {
+                    index: 2,
+                    name: "EXT-X-TARGETDURATION".to_string(),
+                },
+            ]
+        );
+        let error = ValidationError::Custom {
+            rule_id: "relative-segment-uris".to_string(),
+            index: Some(2),
+            message: "a.ts is not relative".to_string(),
+        };
+        let value = serde_json::to_value(&error).unwrap();
+        assert_eq!(
+            value,
+            json!({"custom": {
+                "rule-id": "relative-segment-uris",
+                "index": 2,
+                "message": "a.ts is not relative",
+            }})
+        );
+        assert_eq!(
+            serde_json::from_value::<ValidationError>(value).unwrap(),
+            error
+        );
+    }
+
+    mod round_trip {
+        use crate::m3u8::parser::AttributeValue;
+        use crate::m3u8::playlist::Playlist;
+        use crate::m3u8::tags::Tag;
+        use crate::m3u8::values::{
+            ByteRange, DateTime, Decimal, InitializationVector, KeyMethod, MediaType, PlaylistType,
+            Resolution, TimeOffset,
+        };
+        use proptest::collection::{btree_map, vec};
+        use proptest::option::of;
+        use proptest::prelude::*;
+
+        /// The content of a quoted string or a URI line.
+        fn text() -> impl Strategy<Value = String> {
+            "[a-z0-9./:_-]{1,12}"
+        }
+
+        fn optional_text() -> impl Strategy<Value = Option<String>> {
+            of(text())
+        }
+
+        fn enumerated_string() -> impl Strategy<Value = String> {
+            "[A-Z][A-Z0-9-]{0,7}"
+        }
+
+        fn float() -> impl Strategy<Value = f32> {
+            (0u32..1_000_000).prop_map(|n| n as f32 / 1000.0)
+        }
+
+        fn duration() -> impl Strategy<Value = Decimal> {
+            (0i64..100_000_000, 0u32..=6).prop_map(|(value, scale)| Decimal::new(value, scale))
+        }
+
+        fn byte_range() -> impl Strategy<Value = ByteRange> {
+            (any::<u64>(), of(any::<u64>()))
+                .prop_map(|(length, offset)| ByteRange { length, offset })
+        }
+
+        fn resolution() -> impl Strategy<Value = Resolution> {
+            (1u64..10_000, 1u64..10_000).prop_map(|(width, height)| Resolution { width, height })
+        }
+
+        fn date_time() -> impl Strategy<Value = DateTime> {
+            (0i128..4_000_000_000_000_000_000, -720i32..=840, 0usize..=9).prop_map(
+                |(nanos, offset, fraction_digits)| {
+                    DateTime::from_unix_nanos(nanos, offset, fraction_digits)
+                },
+            )
+        }
+
+        fn key_method() -> impl Strategy<Value = KeyMethod> {
+            prop_oneof![
+                Just(KeyMethod::None),
+                Just(KeyMethod::Aes128),
+                Just(KeyMethod::SampleAes),
+                Just(KeyMethod::SampleAesCtr),
+                "X-[A-Z]{1,6}".prop_map(KeyMethod::Other),
+            ]
+        }
+
+        fn media_type() -> impl Strategy<Value = MediaType> {
+            prop_oneof![
+                Just(MediaType::Audio),
+                Just(MediaType::Video),
+                Just(MediaType::Subtitles),
+                Just(MediaType::ClosedCaptions),
+                "X-[A-Z]{1,6}".prop_map(MediaType::Other),
+            ]
+        }
+
+        fn playlist_type() -> impl Strategy<Value = PlaylistType> {
+            prop_oneof![
+                Just(PlaylistType::Event),
+                Just(PlaylistType::Vod),
+                "X-[A-Z]{1,6}".prop_map(PlaylistType::Other),
+            ]
+        }
+
+        fn attribute_value() -> impl Strategy<Value = AttributeValue> {
+            prop_oneof![
+                any::<u64>().prop_map(AttributeValue::DecimalInteger),
+                "0x[0-9A-F]{1,16}".prop_map(AttributeValue::HexadecimalSequence),
+                (0u32..1_000_000)
+                    .prop_map(|n| AttributeValue::DecimalFloatingPoint(n as f64 / 100.0)),
+                (1u32..1_000_000)
+                    .prop_map(|n| AttributeValue::SignedDecimalFloatingPoint(-(n as f64) / 100.0)),
+                text().prop_map(AttributeValue::QuotedString),
+                enumerated_string().prop_map(AttributeValue::EnumeratedString),
+                (any::<u64>(), any::<u64>())
+                    .prop_map(|(width, height)| AttributeValue::DecimalResolution(width, height)),
+            ]
+        }
+
+        fn media_segment_tag() -> impl Strategy<Value = Tag> {
+            prop_oneof![
+                (
+                    text(),
+                    duration(),
+                    of("[A-Za-z0-9]([A-Za-z0-9 ,.]{0,10}[A-Za-z0-9])?")
+                )
+                    .prop_map(|(uri, duration, title)| Tag::ExtInf(uri, duration, title)),
+                byte_range().prop_map(Tag::ExtXByteRange),
+                Just(Tag::ExtXDiscontinuity),
+                (
+                    key_method(),
+                    optional_text(),
+                    of(any::<u128>().prop_map(InitializationVector::new)),
+                    optional_text(),
+                    optional_text(),
+                )
+                    .prop_map(|(method, uri, iv, keyformat, keyformatversions)| {
+                        Tag::ExtXKey {
+                            method,
+                            uri,
+                            iv,
+                            keyformat,
+                            keyformatversions,
+                            other: Vec::new(),
+                        }
+                    }),
+                (text(), of(byte_range())).prop_map(|(uri, byterange)| Tag::ExtXMap {
+                    uri,
+                    byterange,
+                    other: Vec::new(),
+                }),
+                date_time().prop_map(Tag::ExtXProgramDateTime),
+                (
+                    (text(), optional_text(), date_time(), of(date_time())),
+                    (of(float()), of(float()), of(any::<bool>()), optional_text()),
+                    btree_map("X-[A-Z]{1,6}", attribute_value(), 0..4),
+                )
+                    .prop_map(
+                        |(
+                            (id, class, start_date, end_date),
+                            (duration, planned_duration, end_on_next, cue),
+                            client_attributes,
+                        )| Tag::ExtXDateRange {
+                            id,
+                            class,
+                            start_date,
+                            end_date,
+                            duration,
+                            planned_duration,
+                            end_on_next,
+                            cue,
+                            client_attributes: client_attributes.into_iter().collect(),
+                            other: Vec::new(),
+                        }
+                    ),
+                Just(Tag::ExtXGap),
+                any::<u32>().prop_map(Tag::ExtXBitrate),
+                (text(), of(float()), of(any::<bool>())).prop_map(
+                    |(uri, duration, independent)| Tag::ExtXPart {
+                        uri,
+                        duration,
+                        independent,
+                        other: Vec::new(),
+                    }
+                ),
+            ]
+        }
+
+        fn media_playlist_tag() -> impl Strategy<Value = Tag> {
+            prop_oneof![
+                any::<u64>().prop_map(Tag::ExtXTargetDuration),
+                any::<u64>().prop_map(Tag::ExtXMediaSequence),
+                any::<u32>().prop_map(Tag::ExtXDiscontinuitySequence),
+                Just(Tag::ExtXEndList),
+                playlist_type().prop_map(Tag::ExtXPlaylistType),
+                (
+                    of(any::<bool>()),
+                    of(any::<bool>()),
+                    of(any::<bool>()),
+                    of(float()),
+                    of(any::<bool>()),
+                    of(float()),
+                    of(float()),
+                    of(float()),
+                    of(any::<bool>()),
+                )
+                    .prop_map(
+                        |(
+                            can_play,
+                            can_seek,
+                            can_pause,
+                            min_buffer_time,
+                            can_block_reload,
+                            hold_back,
+                            part_hold_back,
+                            can_skip_until,
+                            can_skip_dateranges,
+                        )| Tag::ExtXServerControl {
+                            can_play,
+                            can_seek,
+                            can_pause,
+                            min_buffer_time,
+                            can_block_reload,
+                            hold_back,
+                            part_hold_back,
+                            can_skip_until,
+                            can_skip_dateranges,
+                            other: Vec::new(),
+                        }
+                    ),
+                (float(), of(any::<u64>())).prop_map(|(part_target_duration, part_number)| {
+                    Tag::ExtXPartInf {
+                        part_target_duration,
+                        part_number,
+                        other: Vec::new(),
+                    }
+                }),
+                (
+                    of(prop_oneof![Just("PART"), Just("MAP")]),
+                    text(),
+                    optional_text()
+                )
+                    .prop_map(|(type_, uri, byterange)| Tag::ExtXPreloadHint {
+                        type_: type_.map(str::to_string),
+                        uri,
+                        byterange,
+                        other: Vec::new(),
+                    }),
+                (text(), of(any::<u64>()), of(any::<u64>()), of(any::<u32>())).prop_map(
+                    |(uri, last_msn, last_part, bandwidth)| Tag::ExtXRenditionReport {
+                        uri,
+                        last_msn,
+                        last_part,
+                        bandwidth,
+                        other: Vec::new(),
+                    }
+                ),
+                (any::<u32>(), optional_text()).prop_map(
+                    |(skipped_segments, recently_removed_dateranges)| Tag::ExtXSkip {
+                        skipped_segments,
+                        recently_removed_dateranges,
+                        other: Vec::new(),
+                    }
+                ),
+            ]
+        }
+
+        fn master_playlist_tag() -> impl Strategy<Value = Tag> {
+            prop_oneof![
+                (
+                    (media_type(), text(), optional_text(), optional_text()),
+                    (
+                        of(any::<bool>()),
+                        of(any::<bool>()),
+                        optional_text(),
+                        optional_text()
+                    ),
+                    (
+                        optional_text(),
+                        optional_text(),
+                        of(any::<bool>()),
+                        optional_text()
+                    ),
+                    (
+                        optional_text(),
+                        optional_text(),
+                        of(any::<u32>()),
+                        of(any::<u32>())
+                    ),
+                )
+                    .prop_map(
+                        |(
+                            (type_, group_id, name, uri),
+                            (default, autoselect, characteristics, language),
+                            (instream_id, language_codec, forced, assoc_language),
+                            (channels, stable_rendition_id, bit_depth, sample_rate),
+                        )| Tag::ExtXMedia {
+                            type_,
+                            group_id,
+                            name,
+                            uri,
+                            default,
+                            autoselect,
+                            characteristics,
+                            language,
+                            instream_id,
+                            language_codec,
+                            forced,
+                            assoc_language,
+                            channels,
+                            stable_rendition_id,
+                            bit_depth,
+                            sample_rate,
+                            other: Vec::new(),
+                        }
+                    ),
+                (
+                    (any::<u32>(), optional_text(), of(resolution()), of(float())),
+                    (optional_text(), optional_text(), optional_text()),
+                    (
+                        of(prop_oneof![Just("NONE".to_string()), text()]),
+                        of(any::<u32>()),
+                        of(enumerated_string()),
+                        of(enumerated_string()),
+                        of(float()),
+                    ),
+                    (optional_text(), optional_text(), optional_text()),
+                    (optional_text(), optional_text(), text()),
+                )
+                    .prop_map(
+                        |(
+                            (bandwidth, codecs, resolution, frame_rate),
+                            (audio, video, subtitle),
+                            (closed_captions, average_bandwidth, hdcp_level, video_range, score),
+                            (supplemental_codecs, allowed_cpc, stable_variant_id),
+                            (pathway_id, req_video_layout, uri),
+                        )| Tag::ExtXStreamInf {
+                            bandwidth,
+                            codecs,
+                            resolution,
+                            frame_rate,
+                            audio,
+                            video,
+                            subtitle,
+                            closed_captions,
+                            average_bandwidth,
+                            hdcp_level,
+                            video_range,
+                            score,
+                            supplemental_codecs,
+                            allowed_cpc,
+                            stable_variant_id,
+                            pathway_id,
+                            req_video_layout,
+                            uri,
+                            other: Vec::new(),
+                        }
+                    ),
+                (
+                    any::<u32>(),
+                    optional_text(),
+                    of(resolution()),
+                    of(float()),
+                    text()
+                )
+                    .prop_map(
+                        |(bandwidth, codecs, resolution, frame_rate, uri)| {
+                            Tag::ExtXIFrameStreamInf {
+                                bandwidth,
+                                codecs,
+                                resolution,
+                                frame_rate,
+                                uri,
+                                other: Vec::new(),
+                            }
+                        }
+                    ),
+                (text(), text(), any::<bool>(), optional_text()).prop_map(
+                    |(id, data, is_uri, language)| Tag::ExtXSessionData {
+                        id,
+                        value: (!is_uri).then(|| data.clone()),
+                        uri: is_uri.then_some(data),
+                        language,
+                        other: Vec::new(),
+                    }
+                ),
+                (
+                    key_method(),
+                    optional_text(),
+                    of(any::<u128>().prop_map(InitializationVector::new))
+                )
+                    .prop_map(|(method, uri, iv)| Tag::ExtXSessionKey {
+                        method,
+                        uri,
+                        iv,
+                        other: Vec::new(),
+                    }),
+            ]
+        }
+
+        fn basic_tag() -> impl Strategy<Value = Tag> {
+            prop_oneof![
+                Just(Tag::ExtM3U),
+                any::<u8>().prop_map(Tag::ExtXVersion),
+                Just(Tag::ExtXIndependentSegments),
+                ((-100_000i32..100_000), of(any::<bool>())).prop_map(|(offset, precise)| {
+                    Tag::ExtXStart {
+                        time_offset: TimeOffset(offset as f64 / 100.0),
+                        precise,
+                        other: Vec::new(),
+                    }
+                }),
+                (text(), text()).prop_map(|(name, value)| Tag::ExtXDefine(format!(
+                    "NAME=\"{}\",VALUE=\"{}\"",
+                    name, value
+                ))),
+                ("EXT-X-CUSTOM-[A-Z]{1,6}", of("[A-Za-z0-9=,:\"]{0,10}"))
+                    .prop_map(|(name, value)| Tag::Unknown { name, value }),
+                "([a-z][a-zA-Z0-9 ]{0,10}[a-zA-Z0-9])?".prop_map(Tag::Comment),
+            ]
+        }
+
+        /// Any tag, with every optional field either present or absent.
+        fn tag() -> impl Strategy<Value = Tag> {
+            prop_oneof![
+                basic_tag(),
+                media_segment_tag(),
+                media_playlist_tag(),
+                master_playlist_tag(),
+            ]
+        }
+
+        proptest! {
+            #![proptest_config(ProptestConfig::with_cases(512))]
 
-        assert_eq!(playlist, Err(vec![ValidationError::InvalidProgramDateTime]));
+            #[test]
+            fn test_tag_round_trip(tags in vec(tag(), 1..8)) {
+                let playlist = Playlist { tags };
+                let text = playlist.to_string();
+                let parsed = Playlist::from_reader(text.as_bytes()).unwrap();
+                prop_assert_eq!(&parsed, &playlist);
+                // The lexer trims lines, so compare the text as well as the tags.
+                prop_assert!(text.lines().all(|line| line == line.trim()));
+                prop_assert_eq!(parsed.to_string(), text);
+
+                #[cfg(feature = "serde")]
+                {
+                    let json = serde_json::to_string(&playlist).unwrap();
+                    prop_assert_eq!(serde_json::from_str::<Playlist>(&json).unwrap(), playlist);
+                }
+            }
+        }
     }
 }
@@ -1,13 +1,42 @@
+//! Validation of playlists against RFC 8216 and related specifications.
+//!
+//! [`ValidationError`] describes a single violation. Each one belongs to a rule with a
+//! stable ID (see [`ValidationError::rule_id`]). A [`Validator`] decides, per
+//! [`Profile`], which rules apply and how severe their findings are.
+//! `Playlist::validate` is a shortcut for the errors of the `Rfc8216` profile.
+//!
+//! Custom rules implement [`ValidationRule`] and are registered with
+//! [`Validator::rule`].
+
+use crate::m3u8::playlist::Playlist;
+use std::fmt;
+use std::sync::Arc;
+
 /// Represents different types of validation errors that can occur when processing an M3U8 playlist.
 ///
 /// This enum captures specific validation issues that may arise when checking the conformity
 /// of a playlist to the M3U8 specification. Each variant represents a distinct error, providing
 /// context for what went wrong during validation.
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(rename_all = "kebab-case", rename_all_fields = "kebab-case")
+)]
 pub enum ValidationError {
     /// Error indicating that the #EXTM3U tag is missing from the playlist.
+    #[cfg_attr(feature = "serde", serde(rename = "missing-extm3u"))]
     MissingExtM3U,
 
+    /// Error indicating that the playlist mixes Master Playlist tags with Media Playlist
+    /// tags.
+    MixedPlaylist {
+        /// The names of the Master Playlist tags found, e.g. `EXT-X-STREAM-INF`.
+        master_tags: Vec<String>,
+        /// The names of the Media Playlist tags found, e.g. `EXTINF`.
+        media_tags: Vec<String>,
+    },
+
     /// Error indicating that the specified version is invalid.
     ///
     /// # Arguments
@@ -15,6 +44,15 @@ pub enum ValidationError {
     /// * `u8` - The invalid version number that was encountered.
     InvalidVersion(u8),
 
+    /// Error indicating that the declared version is lower than the features used by the
+    /// playlist require (RFC 8216 Section 7).
+    VersionTooLow {
+        /// The version declared by `EXT-X-VERSION`, or 1 if the tag is absent.
+        declared: u8,
+        /// The version returned by `Playlist::required_version`.
+        required: u8,
+    },
+
     /// Error indicating that the duration specified is invalid.
     ///
     /// # Arguments
@@ -36,19 +74,40 @@ pub enum ValidationError {
     /// * `String` - The invalid key method that was encountered.
     InvalidKeyMethod(String),
 
+    /// Error indicating that the IV of a key tag does not have 32 hexadecimal digits.
+    ///
+    /// # Arguments
+    ///
+    /// * `String` - The IV as written, e.g. `0x1234567890ABCDEF`.
+    InvalidInitializationVector(String),
+
     /// Error indicating that the URI specified in a map tag is invalid.
     InvalidMapUri,
 
+    /// Error indicating that a session data tag has both a VALUE and a URI, or neither.
+    ///
+    /// # Arguments
+    ///
+    /// * `String` - The DATA-ID of the session data tag.
+    InvalidSessionData(String),
+
     /// Error indicating that the program date and time specified is invalid.
+    ///
+    /// Not reported by `Playlist::validate`: `EXT-X-PROGRAM-DATE-TIME` values are parsed
+    /// when the playlist is read.
     InvalidProgramDateTime,
 
     /// Error indicating that the ID specified in a date range is invalid.
     InvalidDateRangeId,
 
     /// Error indicating that the start date specified in a date range is invalid.
+    ///
+    /// Not reported by `Playlist::validate`: START-DATE values are parsed when the
+    /// playlist is read.
     InvalidDateRangeStartDate,
 
-    /// Error indicating that the end date specified in a date range is invalid.
+    /// Error indicating that the end date specified in a date range precedes its start
+    /// date, or is combined with END-ON-NEXT.
     InvalidDateRangeEndDate,
 
     /// Error indicating that the planned duration specified in a date range is invalid.
@@ -95,7 +154,8 @@ pub enum ValidationError {
     /// Error indicating that a rendition report URI is invalid.
     InvalidRenditionReportUri,
 
-    /// Error indicating that the server control information is invalid.
+    /// Error indicating that the server control information is invalid, e.g.
+    /// CAN-SKIP-DATERANGES=YES without CAN-SKIP-UNTIL.
     InvalidServerControl,
 
     /// Error indicating that the specified start time offset is invalid.
@@ -117,4 +177,546 @@ pub enum ValidationError {
 
     /// Error indicating that the specified start offset is invalid.
     InvalidStartOffset,
+
+    /// Error indicating that the `EXTM3U` tag is not the first line of the playlist.
+    #[cfg_attr(feature = "serde", serde(rename = "extm3u-not-first"))]
+    ExtM3UNotFirst {
+        /// The index of the `EXTM3U` tag.
+        index: usize,
+    },
+
+    /// Error indicating that a tag which may appear at most once is repeated.
+    DuplicateTag {
+        /// The index of the repeated tag.
+        index: usize,
+        /// The tag name, e.g. `EXT-X-TARGETDURATION`.
+        name: String,
+    },
+
+    /// Error indicating that a segment duration, rounded to the nearest integer, exceeds
+    /// the target duration.
+    SegmentDurationExceedsTarget {
+        /// The index of the `EXTINF` tag.
+        index: usize,
+        /// The segment duration.
+        duration: f32,
+        /// The value of `EXT-X-TARGETDURATION`.
+        target_duration: u64,
+    },
+
+    /// Error indicating that `EXT-X-MEDIA-SEQUENCE` appears after the first media segment.
+    MediaSequenceAfterSegment {
+        /// The index of the `EXT-X-MEDIA-SEQUENCE` tag.
+        index: usize,
+    },
+
+    /// Error indicating that `EXT-X-PLAYLIST-TYPE` is neither `EVENT` nor `VOD`.
+    InvalidPlaylistType {
+        /// The index of the `EXT-X-PLAYLIST-TYPE` tag.
+        index: usize,
+        /// The invalid playlist type.
+        playlist_type: String,
+    },
+
+    /// Error indicating that a `VOD` playlist has no `EXT-X-ENDLIST` tag.
+    MissingEndList {
+        /// The index of the `EXT-X-PLAYLIST-TYPE` tag.
+        index: usize,
+    },
+
+    /// Error indicating that a variant stream refers to a rendition group for which there
+    /// is no `EXT-X-MEDIA` tag of the matching TYPE.
+    UnknownRenditionGroup {
+        /// The index of the `EXT-X-STREAM-INF` tag.
+        index: usize,
+        /// The expected rendition TYPE, e.g. `AUDIO`.
+        #[cfg_attr(feature = "serde", serde(rename = "type"))]
+        type_: String,
+        /// The referenced GROUP-ID.
+        group_id: String,
+    },
+
+    /// Error indicating that a variant stream has no CODECS attribute, which RFC 8216
+    /// recommends and the Apple HLS Authoring Specification requires.
+    StreamInfMissingCodecs {
+        /// The index of the `EXT-X-STREAM-INF` tag.
+        index: usize,
+    },
+
+    /// Error indicating that more than one rendition of a group has `DEFAULT=YES`.
+    MultipleDefaultRenditions {
+        /// The index of the second `EXT-X-MEDIA` tag with `DEFAULT=YES`.
+        index: usize,
+        /// The GROUP-ID of the rendition group.
+        group_id: String,
+    },
+
+    /// Error indicating that two renditions of the same group share a NAME.
+    DuplicateRenditionName {
+        /// The index of the `EXT-X-MEDIA` tag that repeats the name.
+        index: usize,
+        /// The GROUP-ID of the rendition group.
+        group_id: String,
+        /// The repeated NAME.
+        name: String,
+    },
+
+    /// Error indicating that the TYPE of an `EXT-X-MEDIA` tag is not `AUDIO`, `VIDEO`,
+    /// `SUBTITLES` or `CLOSED-CAPTIONS`.
+    InvalidMediaType {
+        /// The index of the `EXT-X-MEDIA` tag.
+        index: usize,
+        /// The invalid TYPE.
+        media_type: String,
+    },
+
+    /// Error indicating that a `CLOSED-CAPTIONS` rendition has no INSTREAM-ID.
+    ClosedCaptionsMissingInstreamId {
+        /// The index of the `EXT-X-MEDIA` tag.
+        index: usize,
+    },
+
+    /// Error indicating that a `CLOSED-CAPTIONS` rendition has a URI.
+    ClosedCaptionsWithUri {
+        /// The index of the `EXT-X-MEDIA` tag.
+        index: usize,
+    },
+
+    /// Error indicating that a partial segment is longer than the part target duration.
+    PartDurationExceedsTarget {
+        /// The index of the `EXT-X-PART` tag.
+        index: usize,
+        /// The DURATION of the part.
+        duration: f32,
+        /// The PART-TARGET of `EXT-X-PART-INF`.
+        part_target: f32,
+    },
+
+    /// Error indicating that the playlist contains partial segments but no
+    /// `EXT-X-PART-INF` tag.
+    MissingPartInf {
+        /// The index of the first `EXT-X-PART` tag.
+        index: usize,
+    },
+
+    /// Error indicating that HOLD-BACK is less than three times the target duration.
+    HoldBackTooShort {
+        /// The index of the `EXT-X-SERVER-CONTROL` tag.
+        index: usize,
+        /// The HOLD-BACK value.
+        hold_back: f32,
+        /// The value of `EXT-X-TARGETDURATION`.
+        target_duration: u64,
+    },
+
+    /// Error indicating that PART-HOLD-BACK is less than twice the part target duration.
+    PartHoldBackTooShort {
+        /// The index of the `EXT-X-SERVER-CONTROL` tag.
+        index: usize,
+        /// The PART-HOLD-BACK value.
+        part_hold_back: f32,
+        /// The PART-TARGET of `EXT-X-PART-INF`.
+        part_target: f32,
+    },
+
+    /// Error indicating that CAN-SKIP-UNTIL is less than six times the target duration.
+    CanSkipUntilTooShort {
+        /// The index of the `EXT-X-SERVER-CONTROL` tag.
+        index: usize,
+        /// The CAN-SKIP-UNTIL value.
+        can_skip_until: f32,
+        /// The value of `EXT-X-TARGETDURATION`.
+        target_duration: u64,
+    },
+
+    /// Error indicating that `EXT-X-SKIP` appears although the server did not advertise
+    /// CAN-SKIP-UNTIL.
+    SkipWithoutCanSkipUntil {
+        /// The index of the `EXT-X-SKIP` tag.
+        index: usize,
+    },
+
+    /// Error indicating that a preload hint is followed by a media segment or a partial
+    /// segment instead of appearing at the tail of the playlist.
+    PreloadHintNotAtTail {
+        /// The index of the `EXT-X-PRELOAD-HINT` tag.
+        index: usize,
+    },
+
+    /// A problem reported by a custom [`ValidationRule`].
+    Custom {
+        /// The ID of the rule.
+        rule_id: String,
+        /// The index of the offending tag, if the problem concerns one tag.
+        index: Option<usize>,
+        /// A description of the problem.
+        message: String,
+    },
+
+    /// Error indicating that a tag is defined by RFC 8216bis but not by RFC 8216, e.g.
+    /// `EXT-X-GAP` or `EXT-X-SERVER-CONTROL`.
+    NotInRfc8216 {
+        /// The index of the tag.
+        index: usize,
+        /// The tag name.
+        name: String,
+    },
+}
+
+impl ValidationError {
+    /// Returns the ID of the rule that reports this error, e.g. `duplicate-tag`.
+    ///
+    /// Rule IDs are used by [`Validator`] to change the severity of a rule or to switch
+    /// it off.
+    pub fn rule_id(&self) -> &str {
+        match self {
+            ValidationError::MissingExtM3U => "extm3u-missing",
+            ValidationError::MixedPlaylist { .. } => "mixed-playlist",
+            ValidationError::InvalidVersion(_) => "version",
+            ValidationError::VersionTooLow { .. } => "version-too-low",
+            ValidationError::InvalidDuration(_) => "segment-duration",
+            ValidationError::InvalidTargetDuration(_) => "target-duration",
+            ValidationError::InvalidKeyMethod(_) => "key-method",
+            ValidationError::InvalidInitializationVector(_) => "key-iv",
+            ValidationError::InvalidMapUri => "map-uri",
+            ValidationError::InvalidSessionData(_) => "session-data",
+            ValidationError::InvalidProgramDateTime => "program-date-time",
+            ValidationError::InvalidDateRangeId
+            | ValidationError::InvalidDateRangeStartDate
+            | ValidationError::InvalidDateRangeEndDate
+            | ValidationError::InvalidDateRangePlannedDuration(_) => "daterange",
+            ValidationError::InvalidByteRange(_) => "byterange",
+            ValidationError::MissingMediaFields => "media",
+            ValidationError::InvalidStreamInf(_) => "stream-inf",
+            ValidationError::InvalidIFrameStreamInf(_) => "i-frame-stream-inf",
+            ValidationError::InvalidPartInfo(_) => "part",
+            ValidationError::InvalidPreloadHintUri => "preload-hint",
+            ValidationError::InvalidRenditionReportUri => "rendition-report",
+            ValidationError::InvalidServerControl => "server-control",
+            ValidationError::InvalidStartTimeOffset | ValidationError::InvalidStartOffset => {
+                "start"
+            }
+            ValidationError::InvalidSkipTag(_) => "skip",
+            ValidationError::InvalidBitrate(_) => "bitrate",
+            ValidationError::ExtM3UNotFirst { .. } => "extm3u-first",
+            ValidationError::DuplicateTag { .. } => "duplicate-tag",
+            ValidationError::SegmentDurationExceedsTarget { .. } => {
+                "segment-duration-exceeds-target"
+            }
+            ValidationError::MediaSequenceAfterSegment { .. } => "media-sequence-position",
+            ValidationError::InvalidPlaylistType { .. } => "playlist-type",
+            ValidationError::MissingEndList { .. } => "vod-endlist",
+            ValidationError::UnknownRenditionGroup { .. } => "rendition-group-reference",
+            ValidationError::StreamInfMissingCodecs { .. } => "stream-inf-codecs",
+            ValidationError::MultipleDefaultRenditions { .. } => "rendition-default",
+            ValidationError::DuplicateRenditionName { .. } => "rendition-name",
+            ValidationError::InvalidMediaType { .. } => "media-type",
+            ValidationError::ClosedCaptionsMissingInstreamId { .. }
+            | ValidationError::ClosedCaptionsWithUri { .. } => "closed-captions",
+            ValidationError::PartDurationExceedsTarget { .. } => "part-duration",
+            ValidationError::MissingPartInf { .. } => "part-inf",
+            ValidationError::HoldBackTooShort { .. } => "hold-back",
+            ValidationError::PartHoldBackTooShort { .. } => "part-hold-back",
+            ValidationError::CanSkipUntilTooShort { .. } => "can-skip-until",
+            ValidationError::SkipWithoutCanSkipUntil { .. } => "skip-without-can-skip-until",
+            ValidationError::PreloadHintNotAtTail { .. } => "preload-hint-position",
+            ValidationError::NotInRfc8216 { .. } => "rfc8216bis-tag",
+            ValidationError::Custom { rule_id, .. } => rule_id,
+        }
+    }
+}
+
+/// How serious a finding is.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum Severity {
+    /// Informational only.
+    Info,
+    /// The playlist works with most players but should be fixed.
+    Warning,
+    /// The playlist violates the specification of the profile.
+    Error,
+}
+
+/// A named set of rule severities.
+///
+/// Each profile follows its own specification; see [`Profile::severity`] for the
+/// severity of each rule.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum Profile {
+    /// RFC 8216. Violations of a MUST are errors and violations of a SHOULD, such as a
+    /// variant without CODECS, are warnings. Tags introduced by RFC 8216bis are warnings;
+    /// the Low-Latency HLS rules that check those tags are informational.
+    #[default]
+    Rfc8216,
+    /// RFC 8216bis. Like `Rfc8216`, but RFC 8216bis tags are allowed and the Low-Latency
+    /// HLS rules are errors.
+    Rfc8216bis,
+    /// The Apple HLS Authoring Specification, which builds on RFC 8216bis and turns the
+    /// SHOULD rules into errors. Its additional delivery requirements are checked by
+    /// `authoring::Checker`.
+    AppleHls,
+    /// Only reports problems that commonly break playback as errors; violations most
+    /// players tolerate are warnings.
+    Lenient,
+}
+
+impl Profile {
+    /// Returns the severity of the rule `rule_id` in this profile, or `None` if the rule
+    /// is switched off.
+    ///
+    /// | Rules | `Rfc8216` | `Rfc8216bis` | `AppleHls` | `Lenient` |
+    /// |---|---|---|---|---|
+    /// | `rfc8216bis-tag` | Warning | off | off | off |
+    /// | Low-Latency HLS rules, e.g. `part-hold-back` | Info | Error | Error | Error or Warning |
+    /// | SHOULD rules, e.g. `stream-inf-codecs` | Warning | Warning | Error | Warning |
+    /// | Rules players tolerate, e.g. `vod-endlist` | Error | Error | Error | Warning |
+    /// | All other rules | Error | Error | Error | Error |
+    pub fn severity(&self, rule_id: &str) -> Option<Severity> {
+        match self {
+            Profile::Rfc8216 => match rule_id {
+                "rfc8216bis-tag" | "stream-inf-codecs" => Some(Severity::Warning),
+                "bitrate"
+                | "part"
+                | "part-inf"
+                | "part-duration"
+                | "preload-hint"
+                | "preload-hint-position"
+                | "rendition-report"
+                | "server-control"
+                | "hold-back"
+                | "part-hold-back"
+                | "can-skip-until"
+                | "skip"
+                | "skip-without-can-skip-until" => Some(Severity::Info),
+                _ => Some(Severity::Error),
+            },
+            Profile::Rfc8216bis => match rule_id {
+                "rfc8216bis-tag" => None,
+                "stream-inf-codecs" => Some(Severity::Warning),
+                _ => Some(Severity::Error),
+            },
+            Profile::AppleHls => match rule_id {
+                "rfc8216bis-tag" => None,
+                _ => Some(Severity::Error),
+            },
+            Profile::Lenient => match rule_id {
+                "rfc8216bis-tag" => None,
+                "version-too-low"
+                | "segment-duration-exceeds-target"
+                | "media-sequence-position"
+                | "vod-endlist"
+                | "rendition-default"
+                | "rendition-name"
+                | "closed-captions"
+                | "stream-inf-codecs"
+                | "part-duration"
+                | "hold-back"
+                | "part-hold-back"
+                | "can-skip-until"
+                | "preload-hint-position"
+                | "key-iv" => Some(Severity::Warning),
+                _ => Some(Severity::Error),
+            },
+        }
+    }
+}
+
+/// A validation finding together with the rule that produced it.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub struct Diagnostic {
+    /// The ID of the rule, as returned by [`ValidationError::rule_id`].
+    pub rule_id: String,
+    /// The severity of the finding.
+    pub severity: Severity,
+    /// What is wrong.
+    pub error: ValidationError,
+}
+
+/// A problem reported by a [`ValidationRule`].
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub struct RuleViolation {
+    /// The index of the offending tag in `Playlist::tags`, if the problem concerns one tag.
+    pub index: Option<usize>,
+    /// A description of the problem.
+    pub message: String,
+}
+
+/// A custom validation rule, run by a [`Validator`] after the built-in rules.
+///
+/// # Example
+///
+/// ```
+/// use m3u8_parser::m3u8::playlist::Playlist;
+/// use m3u8_parser::m3u8::tags::Tag;
+/// use m3u8_parser::m3u8::validation::{
+///     Profile, RuleViolation, ValidationError, ValidationRule, Validator,
+/// };
+///
+/// struct RelativeSegmentUris;
+///
+/// impl ValidationRule for RelativeSegmentUris {
+///     fn id(&self) -> &'static str {
+///         "relative-segment-uris"
+///     }
+///
+///     fn check(&self, playlist: &Playlist) -> Vec<RuleViolation> {
+///         playlist
+///             .tags
+///             .iter()
+///             .enumerate()
+///             .filter_map(|(index, tag)| match tag {
+///                 Tag::ExtInf(uri, ..) if uri.contains("://") => Some(RuleViolation {
+///                     index: Some(index),
+///                     message: format!("{} is not relative", uri),
+///                 }),
+///                 _ => None,
+///             })
+///             .collect()
+///     }
+/// }
+///
+/// let data = "#EXTM3U\n#EXT-X-TARGETDURATION:10\n#EXTINF:9,\nhttps://cdn.example.com/a.ts\n";
+/// let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+/// let validator = Validator::new(Profile::Rfc8216).rule(RelativeSegmentUris);
+///
+/// assert_eq!(
+///     playlist.validate_with(&validator),
+///     Err(vec![ValidationError::Custom {
+///         rule_id: "relative-segment-uris".to_string(),
+///         index: Some(2),
+///         message: "https://cdn.example.com/a.ts is not relative".to_string(),
+///     }])
+/// );
+/// ```
+pub trait ValidationRule: Send + Sync {
+    /// Returns the ID of the rule, which must not clash with a built-in rule ID.
+    fn id(&self) -> &'static str;
+
+    /// Returns the severity of the findings, unless the validator overrides it.
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    /// Checks the playlist, returning every problem found.
+    fn check(&self, playlist: &Playlist) -> Vec<RuleViolation>;
+}
+
+/// Validates playlists against a [`Profile`], with optional per-rule overrides.
+///
+/// # Example
+///
+/// ```
+/// use m3u8_parser::m3u8::playlist::Playlist;
+/// use m3u8_parser::m3u8::validation::{Profile, Severity, Validator};
+///
+/// let data = "#EXTM3U\n#EXT-X-TARGETDURATION:5\n#EXTINF:6,\na.ts\n#EXT-X-GAP\n#EXTINF:5,\nb.ts\n";
+/// let playlist = Playlist::from_reader(data.as_bytes()).unwrap();
+///
+/// let diagnostics = Validator::new(Profile::Lenient).validate(&playlist);
+/// assert_eq!(diagnostics.len(), 1);
+/// assert_eq!(diagnostics[0].rule_id, "segment-duration-exceeds-target");
+/// assert_eq!(diagnostics[0].severity, Severity::Warning);
+///
+/// let diagnostics = Validator::new(Profile::Rfc8216)
+///     .disable("segment-duration-exceeds-target")
+///     .validate(&playlist);
+/// assert_eq!(diagnostics[0].rule_id, "rfc8216bis-tag");
+/// ```
+#[derive(Clone, Default)]
+pub struct Validator {
+    profile: Profile,
+    overrides: Vec<(String, Option<Severity>)>,
+    rules: Vec<Arc<dyn ValidationRule>>,
+}
+
+impl fmt::Debug for Validator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rules: Vec<&str> = self.rules.iter().map(|rule| rule.id()).collect();
+        f.debug_struct("Validator")
+            .field("profile", &self.profile)
+            .field("overrides", &self.overrides)
+            .field("rules", &rules)
+            .finish()
+    }
+}
+
+impl Validator {
+    /// Creates a new `Validator` for the given profile.
+    pub fn new(profile: Profile) -> Self {
+        Self {
+            profile,
+            overrides: Vec::new(),
+            rules: Vec::new(),
+        }
+    }
+
+    /// Registers a custom rule, which runs after the built-in rules.
+    pub fn rule<R: ValidationRule + 'static>(mut self, rule: R) -> Self {
+        self.rules.push(Arc::new(rule));
+        self
+    }
+
+    /// Switches off the rule `rule_id`.
+    pub fn disable(self, rule_id: &str) -> Self {
+        self.with_override(rule_id, None)
+    }
+
+    /// Reports findings of the rule `rule_id` with the given severity, switching the rule
+    /// on if the profile has it off.
+    pub fn severity(self, rule_id: &str, severity: Severity) -> Self {
+        self.with_override(rule_id, Some(severity))
+    }
+
+    fn with_override(mut self, rule_id: &str, severity: Option<Severity>) -> Self {
+        self.overrides.retain(|(id, _)| id != rule_id);
+        self.overrides.push((rule_id.to_string(), severity));
+        self
+    }
+
+    /// Returns the severity of the rule `rule_id`, or `None` if it is switched off.
+    pub fn rule_severity(&self, rule_id: &str) -> Option<Severity> {
+        match self.overrides.iter().find(|(id, _)| id == rule_id) {
+            Some((_, severity)) => *severity,
+            None => match self.rules.iter().find(|rule| rule.id() == rule_id) {
+                Some(rule) => Some(rule.severity()),
+                None => self.profile.severity(rule_id),
+            },
+        }
+    }
+
+    /// Validates the playlist, returning every finding of an enabled rule in the order
+    /// the rules reported them.
+    pub fn validate(&self, playlist: &Playlist) -> Vec<Diagnostic> {
+        let custom = self.rules.iter().flat_map(|rule| {
+            rule.check(playlist)
+                .into_iter()
+                .map(|violation| ValidationError::Custom {
+                    rule_id: rule.id().to_string(),
+                    index: violation.index,
+                    message: violation.message,
+                })
+        });
+
+        playlist
+            .check()
+            .into_iter()
+            .chain(custom)
+            .filter_map(|error| {
+                let rule_id = error.rule_id().to_string();
+                self.rule_severity(&rule_id).map(|severity| Diagnostic {
+                    rule_id,
+                    severity,
+                    error,
+                })
+            })
+            .collect()
+    }
 }
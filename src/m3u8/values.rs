@@ -0,0 +1,780 @@
+//! Typed values carried by tags.
+//!
+//! Each type implements `FromStr` for the form it takes in a playlist and `Display` to
+//! write it back, e.g. `"1000@200".parse::<ByteRange>()`.
+//!
+//! [`KeyMethod`], [`MediaType`] and [`PlaylistType`] keep values that do not parse as
+//! `Other`, so a playlist using them can still be read. `Playlist::validate` reports them.
+//!
+//! With the `serde` feature, every type except [`TimeOffset`] is serialized as the string
+//! it is written as in a playlist, e.g. `"1000@200"`. `TimeOffset` is a number.
+
+use crate::m3u8::error::ParseValueError;
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// Returns `true` if `value` is a non-empty string of ASCII digits.
+fn is_digits(value: &str) -> bool {
+    !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Parses a non-empty string of ASCII digits into an unsigned integer.
+fn parse_digits<T: FromStr>(value: &str) -> Option<T> {
+    is_digits(value).then(|| value.parse().ok()).flatten()
+}
+
+/// Implements `Serialize` and `Deserialize` through `Display` and `FromStr`. With a
+/// second argument, strings that do not parse are kept in that variant.
+#[cfg(feature = "serde")]
+macro_rules! serde_string {
+    ($type:ty) => {
+        serde_string!($type, |value: String| value.parse::<$type>());
+    };
+    ($type:ty, $other:path) => {
+        serde_string!($type, |value: String| {
+            Ok::<_, ParseValueError>(value.parse().unwrap_or_else(|_| $other(value)))
+        });
+    };
+    ($type:ty, $parse:expr) => {
+        impl serde::Serialize for $type {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $type {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let value = <String as serde::Deserialize>::deserialize(deserializer)?;
+                $parse(value).map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "serde")]
+serde_string!(Decimal);
+#[cfg(feature = "serde")]
+serde_string!(ByteRange);
+#[cfg(feature = "serde")]
+serde_string!(Resolution);
+#[cfg(feature = "serde")]
+serde_string!(KeyMethod, KeyMethod::Other);
+#[cfg(feature = "serde")]
+serde_string!(InitializationVector);
+#[cfg(feature = "serde")]
+serde_string!(MediaType, MediaType::Other);
+#[cfg(feature = "serde")]
+serde_string!(PlaylistType, PlaylistType::Other);
+#[cfg(feature = "serde")]
+serde_string!(DateTime);
+
+/// The most fractional digits a [`Decimal`] can have.
+const MAX_SCALE: u32 = 18;
+
+/// An exact decimal number, e.g. the duration of an `EXTINF` tag.
+///
+/// The number is stored as an integer and a count of fractional digits, so parsing and
+/// writing it reproduces the original text: `5.0050` stays `5.0050`. Comparisons are
+/// numeric, so `5.005` equals `5.0050`, and sums are exact or fail with `None` when they overflow.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Decimal {
+    value: i64,
+    scale: u32,
+}
+
+impl Decimal {
+    /// Zero, written `0`.
+    pub const ZERO: Decimal = Decimal { value: 0, scale: 0 };
+
+    /// Creates the decimal `value * 10^-scale`, e.g. `Decimal::new(5005, 3)` is `5.005`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scale` is greater than 18.
+    pub fn new(value: i64, scale: u32) -> Self {
+        assert!(scale <= MAX_SCALE, "decimal scale {} is too large", scale);
+        Self { value, scale }
+    }
+
+    /// Returns the shortest decimal that converts back to `value`, e.g. `5.005` for
+    /// `5.005f32`, or `None` if `value` is not finite or too large.
+    pub fn from_f32(value: f32) -> Option<Self> {
+        value.to_string().parse().ok()
+    }
+
+    /// Returns the shortest decimal that converts back to `value`, or `None` if `value`
+    /// is not finite or too large.
+    pub fn from_f64(value: f64) -> Option<Self> {
+        value.to_string().parse().ok()
+    }
+
+    /// Returns the unscaled integer value, e.g. `5005` for `5.005`.
+    pub fn value(&self) -> i64 {
+        self.value
+    }
+
+    /// Returns the number of fractional digits, e.g. `3` for `5.005`.
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    /// Returns the nearest floating-point number.
+    pub fn as_f64(&self) -> f64 {
+        self.to_string().parse().unwrap()
+    }
+
+    /// Returns `true` if the number has no fractional part, e.g. for `6` and `6.00`.
+    pub fn is_integer(&self) -> bool {
+        self.value % 10i64.pow(self.scale) == 0
+    }
+
+    /// Rounds the number to `scale` fractional digits, rounding halves away from zero.
+    /// A larger `scale` than the current one pads the number with zeros, e.g. `6` becomes
+    /// `6.000` with a scale of 3.
+    ///
+    /// Returns `None` if `scale` is greater than 18 or the padded number does not fit.
+    /// Rounding to fewer digits always succeeds.
+    pub fn round(&self, scale: u32) -> Option<Self> {
+        if scale > MAX_SCALE {
+            return None;
+        }
+        let value = if scale >= self.scale {
+            self.scaled(scale)
+        } else {
+            let divisor = 10i128.pow(self.scale - scale);
+            let value = self.value as i128;
+            let rounded = (value.abs() + divisor / 2) / divisor;
+            if value < 0 {
+                -rounded
+            } else {
+                rounded
+            }
+        };
+        Self::from_i128(value, scale)
+    }
+
+    /// Returns the value scaled to `scale` fractional digits, which must not be less
+    /// than `self.scale`.
+    fn scaled(&self, scale: u32) -> i128 {
+        self.value as i128 * 10i128.pow(scale - self.scale)
+    }
+
+    /// Returns the decimal `value * 10^-scale`, or `None` if `value` does not fit.
+    fn from_i128(value: i128, scale: u32) -> Option<Self> {
+        let value = i64::try_from(value).ok()?;
+        Some(Self { value, scale })
+    }
+
+    /// Adds two decimals exactly, returning `None` if the result does not fit. The result
+    /// has the larger scale of the two.
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        let scale = self.scale.max(other.scale);
+        Self::from_i128(self.scaled(scale) + other.scaled(scale), scale)
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = ParseValueError;
+
+    /// Parses an optionally signed decimal number with up to 18 fractional digits, e.g.
+    /// `5.005` or `-12`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseValueError::new("decimal", s);
+        let unsigned = s.strip_prefix('-').unwrap_or(s);
+        let (integer, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        if !is_digits(integer)
+            || (unsigned.contains('.') && !is_digits(fraction))
+            || fraction.len() > MAX_SCALE as usize
+        {
+            return Err(error());
+        }
+        let digits: String = [integer, fraction].concat();
+        let value: i64 = digits.parse().map_err(|_| error())?;
+        Ok(Decimal {
+            value: if s.starts_with('-') { -value } else { value },
+            scale: fraction.len() as u32,
+        })
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.value < 0 { "-" } else { "" };
+        let digits = self.value.unsigned_abs().to_string();
+        if self.scale == 0 {
+            return write!(f, "{}{}", sign, digits);
+        }
+        let digits = format!("{:0>width$}", digits, width = self.scale as usize + 1);
+        let (integer, fraction) = digits.split_at(digits.len() - self.scale as usize);
+        write!(f, "{}{}.{}", sign, integer, fraction)
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let scale = self.scale.max(other.scale);
+        self.scaled(scale).cmp(&other.scaled(scale))
+    }
+}
+
+/// How floating-point durations are written, e.g. those given to
+/// `PlaylistBuilder::extinf`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Precision {
+    /// The shortest decimal that converts back to the same number, e.g. `5.005`.
+    #[default]
+    Shortest,
+    /// A fixed number of fractional digits, e.g. `5.005` and `6.000` with `Fixed(3)`.
+    /// `Fixed(0)` writes integers, which any version of the protocol accepts. A scale
+    /// above 18, or one a number cannot be padded to without overflowing, is lowered to
+    /// the largest that fits.
+    Fixed(u32),
+}
+
+impl Precision {
+    /// Applies the policy to a decimal.
+    pub fn apply(&self, decimal: Decimal) -> Decimal {
+        match self {
+            Precision::Shortest => decimal,
+            Precision::Fixed(scale) => (0..=(*scale).min(MAX_SCALE))
+                .rev()
+                .find_map(|scale| decimal.round(scale))
+                .unwrap_or(decimal),
+        }
+    }
+}
+
+/// A sub-range of a resource, written `<length>[@<offset>]` (RFC 8216 Section 4.3.2.2).
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ByteRange {
+    /// The length of the sub-range in bytes.
+    pub length: u64,
+    /// The offset of the sub-range from the start of the resource. If absent, the
+    /// sub-range starts after the sub-range of the previous segment.
+    pub offset: Option<u64>,
+}
+
+impl FromStr for ByteRange {
+    type Err = ParseValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseValueError::new("byte range", s);
+        let (length, offset) = match s.split_once('@') {
+            Some((length, offset)) => (length, Some(parse_digits(offset).ok_or_else(error)?)),
+            None => (s, None),
+        };
+        Ok(ByteRange {
+            length: parse_digits(length).ok_or_else(error)?,
+            offset,
+        })
+    }
+}
+
+impl fmt::Display for ByteRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.offset {
+            Some(offset) => write!(f, "{}@{}", self.length, offset),
+            None => write!(f, "{}", self.length),
+        }
+    }
+}
+
+/// The pixel resolution of a video, written `<width>x<height>`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Resolution {
+    pub width: u64,
+    pub height: u64,
+}
+
+impl FromStr for Resolution {
+    type Err = ParseValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseValueError::new("resolution", s);
+        let (width, height) = s.split_once('x').ok_or_else(error)?;
+        Ok(Resolution {
+            width: parse_digits(width).ok_or_else(error)?,
+            height: parse_digits(height).ok_or_else(error)?,
+        })
+    }
+}
+
+impl fmt::Display for Resolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)
+    }
+}
+
+/// The encryption method of `EXT-X-KEY` and `EXT-X-SESSION-KEY`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum KeyMethod {
+    /// `NONE`: media segments are not encrypted.
+    None,
+    /// `AES-128`: media segments are completely encrypted with AES-128 in CBC mode.
+    Aes128,
+    /// `SAMPLE-AES`: media samples are encrypted, e.g. for FairPlay Streaming.
+    SampleAes,
+    /// `SAMPLE-AES-CTR`: media samples are encrypted in CTR mode (RFC 8216bis).
+    SampleAesCtr,
+    /// A method this crate does not know, kept as written.
+    Other(String),
+}
+
+impl FromStr for KeyMethod {
+    type Err = ParseValueError;
+
+    /// Parses a known method. Unknown methods are an error; parsers that want to keep
+    /// them use `KeyMethod::Other`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "NONE" => Ok(KeyMethod::None),
+            "AES-128" => Ok(KeyMethod::Aes128),
+            "SAMPLE-AES" => Ok(KeyMethod::SampleAes),
+            "SAMPLE-AES-CTR" => Ok(KeyMethod::SampleAesCtr),
+            _ => Err(ParseValueError::new("key method", s)),
+        }
+    }
+}
+
+impl fmt::Display for KeyMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyMethod::None => write!(f, "NONE"),
+            KeyMethod::Aes128 => write!(f, "AES-128"),
+            KeyMethod::SampleAes => write!(f, "SAMPLE-AES"),
+            KeyMethod::SampleAesCtr => write!(f, "SAMPLE-AES-CTR"),
+            KeyMethod::Other(method) => write!(f, "{}", method),
+        }
+    }
+}
+
+/// The 128-bit initialization vector of `EXT-X-KEY`, written as a hexadecimal sequence.
+///
+/// The IV keeps the form it was parsed from, so writing it reproduces the prefix, the
+/// number of digits and, unless the digits mix cases, their case. Comparisons only look at
+/// the value, so `0xABCD` equals `0x0000000000000000000000000000abcd`.
+#[derive(Debug, Clone, Copy)]
+pub struct InitializationVector {
+    value: u128,
+    digits: u8,
+    uppercase_prefix: bool,
+    uppercase_digits: bool,
+}
+
+impl InitializationVector {
+    /// Creates an IV written as `0x` followed by 32 lowercase digits.
+    pub fn new(value: u128) -> Self {
+        Self {
+            value,
+            digits: 32,
+            uppercase_prefix: false,
+            uppercase_digits: false,
+        }
+    }
+
+    /// Returns the value of the IV.
+    pub fn value(&self) -> u128 {
+        self.value
+    }
+
+    /// Returns the number of hexadecimal digits the IV was written with. RFC 8216 requires
+    /// 32; shorter sequences are accepted when parsing and reported by `Playlist::validate`.
+    pub fn digits(&self) -> usize {
+        self.digits as usize
+    }
+
+    /// Returns the initialization vector as big-endian bytes.
+    pub fn to_bytes(&self) -> [u8; 16] {
+        self.value.to_be_bytes()
+    }
+}
+
+impl PartialEq for InitializationVector {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl Eq for InitializationVector {}
+
+impl FromStr for InitializationVector {
+    type Err = ParseValueError;
+
+    /// Parses a hexadecimal sequence of 1 to 32 digits, with a `0x` or `0X` prefix.
+    /// Shorter sequences are padded with leading zeros.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = s
+            .strip_prefix("0x")
+            .or_else(|| s.strip_prefix("0X"))
+            .filter(|digits| {
+                (1..=32).contains(&digits.len()) && digits.bytes().all(|b| b.is_ascii_hexdigit())
+            })
+            .ok_or_else(|| ParseValueError::new("128-bit initialization vector", s))?;
+        Ok(InitializationVector {
+            value: u128::from_str_radix(digits, 16).unwrap(),
+            digits: digits.len() as u8,
+            uppercase_prefix: s.starts_with("0X"),
+            uppercase_digits: digits.bytes().any(|b| b.is_ascii_uppercase())
+                && !digits.bytes().any(|b| b.is_ascii_lowercase()),
+        })
+    }
+}
+
+impl fmt::Display for InitializationVector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let prefix = if self.uppercase_prefix { "0X" } else { "0x" };
+        let width = self.digits as usize;
+        if self.uppercase_digits {
+            write!(f, "{}{:0width$X}", prefix, self.value)
+        } else {
+            write!(f, "{}{:0width$x}", prefix, self.value)
+        }
+    }
+}
+
+/// The TYPE of an `EXT-X-MEDIA` rendition.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum MediaType {
+    Audio,
+    Video,
+    Subtitles,
+    ClosedCaptions,
+    /// A type this crate does not know, kept as written.
+    Other(String),
+}
+
+impl FromStr for MediaType {
+    type Err = ParseValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "AUDIO" => Ok(MediaType::Audio),
+            "VIDEO" => Ok(MediaType::Video),
+            "SUBTITLES" => Ok(MediaType::Subtitles),
+            "CLOSED-CAPTIONS" => Ok(MediaType::ClosedCaptions),
+            _ => Err(ParseValueError::new("media type", s)),
+        }
+    }
+}
+
+impl fmt::Display for MediaType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MediaType::Audio => write!(f, "AUDIO"),
+            MediaType::Video => write!(f, "VIDEO"),
+            MediaType::Subtitles => write!(f, "SUBTITLES"),
+            MediaType::ClosedCaptions => write!(f, "CLOSED-CAPTIONS"),
+            MediaType::Other(type_) => write!(f, "{}", type_),
+        }
+    }
+}
+
+/// The value of `EXT-X-PLAYLIST-TYPE`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum PlaylistType {
+    /// Media segments can only be added to the end of the playlist.
+    Event,
+    /// The playlist cannot change.
+    Vod,
+    /// A type this crate does not know, kept as written.
+    Other(String),
+}
+
+impl FromStr for PlaylistType {
+    type Err = ParseValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "EVENT" => Ok(PlaylistType::Event),
+            "VOD" => Ok(PlaylistType::Vod),
+            _ => Err(ParseValueError::new("playlist type", s)),
+        }
+    }
+}
+
+impl fmt::Display for PlaylistType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlaylistType::Event => write!(f, "EVENT"),
+            PlaylistType::Vod => write!(f, "VOD"),
+            PlaylistType::Other(playlist_type) => write!(f, "{}", playlist_type),
+        }
+    }
+}
+
+/// The TIME-OFFSET of `EXT-X-START`, in seconds. Negative offsets count from the end of
+/// the playlist.
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct TimeOffset(pub f64);
+
+impl FromStr for TimeOffset {
+    type Err = ParseValueError;
+
+    /// Parses a signed decimal floating-point number, e.g. `-12.5` or `10`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let unsigned = s.strip_prefix('-').unwrap_or(s);
+        let (integer, fraction) = unsigned.split_once('.').unwrap_or((unsigned, "0"));
+        if !is_digits(integer) || !is_digits(fraction) {
+            return Err(ParseValueError::new("time offset", s));
+        }
+        s.parse()
+            .map(TimeOffset)
+            .map_err(|_| ParseValueError::new("time offset", s))
+    }
+}
+
+impl fmt::Display for TimeOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+const NANOS_PER_SECOND: i128 = 1_000_000_000;
+const NANOS_PER_MINUTE: i128 = 60 * NANOS_PER_SECOND;
+const NANOS_PER_DAY: i128 = 24 * 60 * NANOS_PER_MINUTE;
+
+/// A date and time with a time zone offset, e.g. the value of `EXT-X-PROGRAM-DATE-TIME`.
+///
+/// Parses the ISO 8601 / RFC 3339 form used by HLS, e.g. `2024-11-05T12:00:00.500+01:00`,
+/// with any number of fractional digits and an offset of `Z`, `+HH:MM` or `+HHMM`.
+/// Parsed values are written back as they were read. Comparisons are between instants,
+/// so `12:00:00Z` equals `13:00:00+01:00`.
+#[derive(Debug, Clone)]
+pub struct DateTime {
+    /// Nanoseconds since 1970-01-01T00:00:00Z.
+    nanos: i128,
+    /// The offset from UTC in minutes.
+    offset: i32,
+    text: String,
+}
+
+impl DateTime {
+    /// Creates the date-time `nanos` nanoseconds after 1970-01-01T00:00:00Z, written with
+    /// an offset of `offset` minutes from UTC and at least `fraction_digits` fractional
+    /// digits.
+    pub fn from_unix_nanos(nanos: i128, offset: i32, fraction_digits: usize) -> Self {
+        let local = nanos + offset as i128 * NANOS_PER_MINUTE;
+        let days = local.div_euclid(NANOS_PER_DAY);
+        let time = local.rem_euclid(NANOS_PER_DAY);
+        let (year, month, day) = civil_from_days(days as i64);
+        let seconds = time / NANOS_PER_SECOND;
+
+        let mut text = format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            year,
+            month,
+            day,
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        );
+        let fraction = format!("{:09}", time % NANOS_PER_SECOND);
+        let fraction = fraction.trim_end_matches('0');
+        let digits = fraction.len().max(fraction_digits.min(9));
+        if digits > 0 {
+            text.push_str(&format!(".{:0<width$}", fraction, width = digits));
+        }
+        if offset == 0 {
+            text.push('Z');
+        } else {
+            let sign = if offset < 0 { '-' } else { '+' };
+            let offset = offset.abs();
+            text.push_str(&format!("{}{:02}:{:02}", sign, offset / 60, offset % 60));
+        }
+
+        Self {
+            nanos,
+            offset,
+            text,
+        }
+    }
+
+    /// Returns the number of nanoseconds since 1970-01-01T00:00:00Z.
+    pub fn unix_nanos(&self) -> i128 {
+        self.nanos
+    }
+
+    /// Returns the offset from UTC in minutes, e.g. `60` for `+01:00`.
+    pub fn offset(&self) -> i32 {
+        self.offset
+    }
+
+    /// Returns the date-time `seconds` later, with the same offset and at least as many
+    /// fractional digits. Digits beyond nanoseconds are dropped.
+    pub fn add_seconds(&self, seconds: Decimal) -> Self {
+        let nanos = if seconds.scale() <= 9 {
+            seconds.value() as i128 * 10i128.pow(9 - seconds.scale())
+        } else {
+            seconds.value() as i128 / 10i128.pow(seconds.scale() - 9)
+        };
+        Self::from_unix_nanos(self.nanos + nanos, self.offset, self.fraction_digits())
+    }
+
+    /// Returns the number of fractional digits of the seconds as written.
+    fn fraction_digits(&self) -> usize {
+        match self.text.split_once('.') {
+            Some((_, rest)) => rest.bytes().take_while(u8::is_ascii_digit).count(),
+            None => 0,
+        }
+    }
+}
+
+impl FromStr for DateTime {
+    type Err = ParseValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseValueError::new("date-time", s);
+        let field = |range: std::ops::Range<usize>| {
+            s.get(range)
+                .filter(|digits| is_digits(digits))
+                .and_then(|digits| digits.parse::<i64>().ok())
+                .ok_or_else(error)
+        };
+        let separators = [(4, b'-'), (7, b'-'), (13, b':'), (16, b':')];
+        if s.len() < 20
+            || separators.iter().any(|(i, c)| s.as_bytes()[*i] != *c)
+            || !matches!(s.as_bytes()[10], b'T' | b't')
+        {
+            return Err(error());
+        }
+        let (year, month, day) = (field(0..4)?, field(5..7)?, field(8..10)?);
+        let (hour, minute, second) = (field(11..13)?, field(14..16)?, field(17..19)?);
+        if !(1..=12).contains(&month)
+            || day < 1
+            || day > days_in_month(year, month)
+            || hour > 23
+            || minute > 59
+            || second > 60
+        {
+            return Err(error());
+        }
+
+        let mut rest = &s[19..];
+        let mut fraction = 0;
+        if let Some(after_dot) = rest.strip_prefix('.') {
+            let digits = after_dot.bytes().take_while(u8::is_ascii_digit).count();
+            if digits == 0 {
+                return Err(error());
+            }
+            let nanos = format!("{:0<9}", &after_dot[..digits.min(9)]);
+            fraction = nanos.parse::<i128>().map_err(|_| error())?;
+            rest = &after_dot[digits..];
+        }
+
+        let offset = match rest {
+            "Z" | "z" => 0,
+            _ => {
+                let sign = match rest.as_bytes().first() {
+                    Some(b'+') => 1,
+                    Some(b'-') => -1,
+                    _ => return Err(error()),
+                };
+                let (hours, minutes) = match rest.len() {
+                    6 if rest.as_bytes()[3] == b':' => (rest.get(1..3), rest.get(4..6)),
+                    5 => (rest.get(1..3), rest.get(3..5)),
+                    _ => return Err(error()),
+                };
+                let (Some(hours), Some(minutes)) = (
+                    hours.and_then(parse_digits::<i32>),
+                    minutes.and_then(parse_digits::<i32>),
+                ) else {
+                    return Err(error());
+                };
+                if hours > 23 || minutes > 59 {
+                    return Err(error());
+                }
+                sign * (hours * 60 + minutes)
+            }
+        };
+
+        let local = days_from_civil(year, month, day) as i128 * NANOS_PER_DAY
+            + ((hour * 60 + minute) * 60 + second) as i128 * NANOS_PER_SECOND
+            + fraction;
+        Ok(Self {
+            nanos: local - offset as i128 * NANOS_PER_MINUTE,
+            offset,
+            text: s.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+impl PartialEq for DateTime {
+    fn eq(&self, other: &Self) -> bool {
+        self.nanos == other.nanos
+    }
+}
+
+impl Eq for DateTime {}
+
+impl PartialOrd for DateTime {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DateTime {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.nanos.cmp(&other.nanos)
+    }
+}
+
+fn is_leap_year(year: i64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Returns the number of days since 1970-01-01 of a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Returns the proleptic Gregorian date `days` days after 1970-01-01.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+    (year, month, day)
+}